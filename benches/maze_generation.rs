@@ -1,9 +1,9 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
-use maze_generator::algorithms::{MazeGenerator, RecursiveBacktracking, Kruskal, Prim, AldousBroder};
+use maze_generator::algorithms::{MazeGenerator, RecursiveBacktracking, Registry};
 use maze_generator::render::render_maze;
 
-fn benchmark_algorithm(c: &mut Criterion, name: &str, generator: Box<dyn MazeGenerator>) {
-    let mut group = c.benchmark_group(name);
+fn benchmark_algorithm(c: &mut Criterion, generator: &dyn MazeGenerator) {
+    let mut group = c.benchmark_group(generator.name());
     
     // Benchmark different sizes
     for size in [10, 20, 50, 100].iter() {
@@ -17,7 +17,9 @@ fn benchmark_algorithm(c: &mut Criterion, name: &str, generator: Box<dyn MazeGen
                         black_box(size),
                         black_box(0.5),
                         black_box(Some(42)), // Fixed seed for consistency
-                    );
+                    )
+                    .expect("benchmark sizes are valid")
+                    .maze;
                     black_box(maze)
                 });
             },
@@ -28,10 +30,10 @@ fn benchmark_algorithm(c: &mut Criterion, name: &str, generator: Box<dyn MazeGen
 }
 
 fn benchmark_all_algorithms(c: &mut Criterion) {
-    benchmark_algorithm(c, "recursive_backtracking", Box::new(RecursiveBacktracking));
-    benchmark_algorithm(c, "kruskal", Box::new(Kruskal));
-    benchmark_algorithm(c, "prim", Box::new(Prim));
-    benchmark_algorithm(c, "aldous_broder", Box::new(AldousBroder));
+    let registry = Registry::builtin();
+    for generator in registry.iter() {
+        benchmark_algorithm(c, generator);
+    }
 }
 
 fn benchmark_complexity(c: &mut Criterion) {
@@ -50,7 +52,9 @@ fn benchmark_complexity(c: &mut Criterion) {
                         black_box(size),
                         black_box(complexity),
                         black_box(Some(42)),
-                    );
+                    )
+                    .expect("benchmark sizes are valid")
+                    .maze;
                     black_box(maze)
                 });
             },
@@ -66,7 +70,10 @@ fn benchmark_solving(c: &mut Criterion) {
     
     for size in [10, 20, 50, 100].iter() {
         // Generate maze once
-        let maze = generator.generate(*size, *size, 0.5, Some(42));
+        let maze = generator
+            .generate(*size, *size, 0.5, Some(42))
+            .expect("benchmark sizes are valid")
+            .maze;
         
         group.bench_with_input(
             BenchmarkId::from_parameter(size),
@@ -88,7 +95,10 @@ fn benchmark_rendering(c: &mut Criterion) {
     let generator = RecursiveBacktracking;
     
     for size in [10, 20, 50, 100].iter() {
-        let maze = generator.generate(*size, *size, 0.5, Some(42));
+        let maze = generator
+            .generate(*size, *size, 0.5, Some(42))
+            .expect("benchmark sizes are valid")
+            .maze;
         let cell_size = 10;
         
         group.bench_with_input(
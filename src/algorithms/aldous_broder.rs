@@ -1,78 +1,170 @@
-use crate::algorithms::MazeGenerator;
+use crate::algorithms::{GenerateError, GeneratorOption, MazeGenerator, Params};
+use crate::grid::{ensure_connectivity, Grid};
 use crate::maze::Maze;
 use rand::Rng;
-use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 use std::collections::HashSet;
 
-pub struct AldousBroder;
+/// Parameters for aldous_broder
+#[derive(Debug, Clone, Default)]
+pub struct AldousBroderParams {
+    /// Sample every spanning tree with equal probability, ignoring `complexity`
+    pub uniform: Option<bool>,
+}
+
+impl AldousBroderParams {
+    /// Override the parameters named in `params`
+    pub fn apply(&mut self, params: &Params) -> Result<(), String> {
+        params.check_keys("aldous_broder", &["uniform"])?;
+        if let Some(uniform) = params.get("uniform")? {
+            self.uniform = Some(uniform);
+        }
+        Ok(())
+    }
+
+    /// The parameters that are set, as they would be written
+    pub fn to_params(&self) -> Params {
+        let mut params = Params::new();
+        if let Some(uniform) = &self.uniform {
+            params.set("uniform", &uniform.to_string());
+        }
+        params
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AldousBroder {
+    pub params: AldousBroderParams,
+}
 
 impl MazeGenerator for AldousBroder {
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze {
-        let mut maze = Maze::new(width, height);
-        let mut rng = match seed {
-            Some(s) => ChaCha8Rng::seed_from_u64(s),
-            None => {
-                let seed = rand::thread_rng().gen();
-                ChaCha8Rng::seed_from_u64(seed)
-            }
-        };
-        let mut visited = HashSet::new();
+    fn name(&self) -> &'static str {
+        "aldous_broder"
+    }
 
-        // Start at a random cell
-        let mut current_x = rng.gen_range(0..width);
-        let mut current_y = rng.gen_range(0..height);
-        visited.insert((current_x, current_y));
+    fn description(&self) -> &'static str {
+        "Random walk that carves into every unvisited cell; unbiased but slow"
+    }
 
-        let total_cells = width * height;
-        let mut visited_count = 1;
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[
+            GeneratorOption {
+                name: "complexity",
+                description: "Randomness of the walk and chance of opening walls into visited cells",
+            },
+            GeneratorOption {
+                name: "uniform",
+                description: "true samples every spanning tree with equal probability, ignoring complexity",
+            },
+        ]
+    }
 
-        // Random walk until all cells are visited
-        while visited_count < total_cells {
-            let neighbors = maze.get_neighbors(current_x, current_y);
-            if neighbors.is_empty() {
-                break;
-            }
+    fn with_params(&self, params: &Params) -> Result<Box<dyn MazeGenerator>, String> {
+        let mut generator = self.clone();
+        generator.params.apply(params)?;
+        Ok(Box::new(generator))
+    }
 
-            // Choose neighbor based on complexity
-            // Lower complexity = prefer first neighbor (more deterministic walk)
-            // Higher complexity = more random selection
-            let next_idx = if neighbors.len() == 1 {
+    fn params(&self) -> Params {
+        self.params.to_params()
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        carve_tree(maze, self.complexity(complexity), rng);
+    }
+
+    fn carve_grid(
+        &self,
+        grid: &mut dyn Grid,
+        complexity: f64,
+        rng: &mut ChaCha8Rng,
+    ) -> Result<(), GenerateError> {
+        carve_tree(grid, self.complexity(complexity), rng);
+        Ok(())
+    }
+
+    fn supports_mask(&self) -> bool {
+        true
+    }
+
+    fn supports_grids(&self) -> bool {
+        true
+    }
+}
+
+impl AldousBroder {
+    /// The complexity to walk with, or None for a plain uniform random walk
+    fn complexity(&self, complexity: f64) -> Option<f64> {
+        (self.params.uniform != Some(true)).then_some(complexity)
+    }
+}
+
+/// Steps per cell a walk that prefers its first neighbour may take
+/// Such a walk can circle one corner of the grid for ever, so past this
+/// the cells it never reached are joined with `ensure_connectivity`.
+const DRIFT_STEPS_PER_CELL: usize = 100;
+
+fn carve_tree(grid: &mut dyn Grid, complexity: Option<f64>, rng: &mut ChaCha8Rng) {
+    let mut visited = HashSet::new();
+
+    // Start at a random cell
+    let mut current = grid.random_cell(rng);
+    visited.insert(current);
+    grid.visit(current);
+
+    let total_cells = grid.active_count();
+    let mut visited_count = 1;
+    let drifts = complexity.is_some_and(|c| c < 0.1);
+    let mut steps_left = total_cells * DRIFT_STEPS_PER_CELL;
+
+    // Random walk until all cells are visited
+    while visited_count < total_cells {
+        let neighbors = grid.neighbors(current);
+        if neighbors.is_empty() || (drifts && steps_left == 0) {
+            break;
+        }
+        steps_left = steps_left.saturating_sub(1);
+
+        // Choose neighbor based on complexity
+        // Lower complexity = prefer first neighbor (more deterministic walk)
+        // Higher complexity = more random selection
+        let next_idx = if neighbors.len() == 1 {
+            0
+        } else if drifts {
+            // Very low complexity: prefer first neighbor (more deterministic)
+            if rng.gen::<f64>() < 0.8 {
                 0
-            } else if complexity < 0.1 {
-                // Very low complexity: prefer first neighbor (more deterministic)
-                if rng.gen::<f64>() < 0.8 {
-                    0
-                } else {
-                    rng.gen_range(1..neighbors.len())
-                }
-            } else {
-                // Higher complexity: fully random
-                rng.gen_range(0..neighbors.len())
-            };
-            let (next_x, next_y) = neighbors[next_idx];
-
-            // If the neighbor hasn't been visited, remove wall and mark as visited
-            if !visited.contains(&(next_x, next_y)) {
-                maze.remove_wall(current_x, current_y, next_x, next_y);
-                visited.insert((next_x, next_y));
-                visited_count += 1;
             } else {
-                // Even if visited, sometimes remove wall based on complexity
-                // Higher complexity = more random wall removals (creates loops)
-                // At complexity 0.0: no loops (perfect maze)
-                // At complexity 1.0: many loops (complex maze)
-                if complexity > 0.0 && rng.gen::<f64>() < complexity * 0.5 {
-                    maze.remove_wall(current_x, current_y, next_x, next_y);
-                }
+                rng.gen_range(1..neighbors.len())
             }
+        } else {
+            // Higher complexity, or uniform: fully random
+            rng.gen_range(0..neighbors.len())
+        };
+        let next = neighbors[next_idx];
 
-            // Move to the neighbor
-            current_x = next_x;
-            current_y = next_y;
+        // If the neighbor hasn't been visited, remove wall and mark as visited
+        if !visited.contains(&next) {
+            grid.link(current, next);
+            visited.insert(next);
+            visited_count += 1;
+        } else {
+            // Even if visited, sometimes remove wall based on complexity
+            // Higher complexity = more random wall removals (creates loops)
+            // At complexity 0.0: no loops (perfect maze)
+            // At complexity 1.0: many loops (complex maze)
+            // A uniform walk opens nothing, keeping the maze a spanning tree
+            if complexity.is_some_and(|c| c > 0.0 && rng.gen::<f64>() < c * 0.5) {
+                grid.link(current, next);
+            }
         }
 
-        maze
+        // Move to the neighbor
+        grid.visit(next);
+        current = next;
     }
-}
 
+    if visited_count < total_cells {
+        ensure_connectivity(grid);
+    }
+}
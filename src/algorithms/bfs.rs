@@ -1,22 +1,31 @@
-use crate::algorithms::MazeGenerator;
+use crate::algorithms::{GeneratorOption, MazeGenerator};
+use crate::grid::Grid;
 use crate::maze::Maze;
 use rand::Rng;
-use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 use std::collections::{HashSet, VecDeque};
 
 pub struct Bfs;
 
 impl MazeGenerator for Bfs {
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze {
-        let mut maze = Maze::new(width, height);
-        let mut rng = match seed {
-            Some(s) => ChaCha8Rng::seed_from_u64(s),
-            None => {
-                let seed = rand::thread_rng().gen();
-                ChaCha8Rng::seed_from_u64(seed)
-            }
-        };
+    fn name(&self) -> &'static str {
+        "bfs"
+    }
+
+    fn description(&self) -> &'static str {
+        "Breadth-first growth from the start; bushy with short paths"
+    }
+
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[GeneratorOption {
+            name: "complexity",
+            description: "How many neighbours of each cell are opened; higher opens fewer",
+        }]
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        let width = maze.width();
+        let height = maze.height();
         let mut queue = VecDeque::new();
         let mut visited = HashSet::new();
 
@@ -25,6 +34,7 @@ impl MazeGenerator for Bfs {
         let start_y = rng.gen_range(0..height);
         queue.push_back((start_x, start_y));
         visited.insert((start_x, start_y));
+        maze.visit(maze.cell_id(start_x, start_y));
 
         while let Some((x, y)) = queue.pop_front() {
             // Get unvisited neighbors
@@ -64,20 +74,17 @@ impl MazeGenerator for Bfs {
                 }
 
                 // Process selected neighbors
-                for i in 0..num_to_process.min(neighbors_to_process.len()) {
-                    let (nx, ny) = neighbors_to_process[i];
+                for &(nx, ny) in neighbors_to_process.iter().take(num_to_process) {
                     
                     // Remove wall between current and neighbor
                     maze.remove_wall(x, y, nx, ny);
                     
                     // Mark as visited and add to queue
                     visited.insert((nx, ny));
+                    maze.visit(maze.cell_id(nx, ny));
                     queue.push_back((nx, ny));
                 }
             }
         }
-
-        maze
     }
 }
-
@@ -1,21 +1,29 @@
-use crate::algorithms::MazeGenerator;
+use crate::algorithms::{GeneratorOption, MazeGenerator};
 use crate::maze::Maze;
 use rand::Rng;
-use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 
 pub struct BinaryTree;
 
 impl MazeGenerator for BinaryTree {
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze {
-        let mut maze = Maze::new(width, height);
-        let mut rng = match seed {
-            Some(s) => ChaCha8Rng::seed_from_u64(s),
-            None => {
-                let seed = rand::thread_rng().gen();
-                ChaCha8Rng::seed_from_u64(seed)
-            }
-        };
+    fn name(&self) -> &'static str {
+        "binary_tree"
+    }
+
+    fn description(&self) -> &'static str {
+        "Each cell opens north or east; strong diagonal bias with two open edges"
+    }
+
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[GeneratorOption {
+            name: "complexity",
+            description: "Randomness of the north or east choice",
+        }]
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        let width = maze.width();
+        let height = maze.height();
 
         // For each cell, carve either north or east
         // Complexity controls direction bias: 0.0 = always first option, 1.0 = random
@@ -54,8 +62,5 @@ impl MazeGenerator for BinaryTree {
                 }
             }
         }
-
-        maze
     }
 }
-
@@ -1,27 +1,174 @@
-use crate::algorithms::MazeGenerator;
+use crate::algorithms::{GeneratorOption, MazeGenerator, Params};
 use crate::maze::Maze;
 use rand::Rng;
-use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
 
-pub struct CellularAutomata;
+/// Birth/survival rule for wall cells, written as "B5678/S5678"
+///
+/// The digits count wall neighbours, including diagonals and the edge of the
+/// maze. An open cell becomes a wall on a birth count, and a wall stays a
+/// wall on a survival count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    birth: [bool; 9],
+    survival: [bool; 9],
+}
 
-impl MazeGenerator for CellularAutomata {
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze {
-        let mut maze = Maze::new(width, height);
-        let mut rng = match seed {
-            Some(s) => ChaCha8Rng::seed_from_u64(s),
-            None => {
-                let seed = rand::thread_rng().gen();
-                ChaCha8Rng::seed_from_u64(seed)
+impl Rule {
+    /// The smoothing rule used unless one is given: a wall wherever five or more neighbours are walls
+    pub const SMOOTH: Rule = Rule {
+        birth: [false, false, false, false, false, true, true, true, true],
+        survival: [false, false, false, false, false, true, true, true, true],
+    };
+
+    /// Whether a cell is a wall in the next generation
+    fn next(&self, is_wall: bool, wall_count: usize) -> bool {
+        if is_wall {
+            self.survival[wall_count]
+        } else {
+            self.birth[wall_count]
+        }
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (birth, survival) = s
+            .trim()
+            .split_once('/')
+            .ok_or_else(|| format!("Rule must look like B5678/S5678, got: {}", s))?;
+        let counts = |part: &str, prefix: char| -> Result<[bool; 9], String> {
+            let digits = part
+                .strip_prefix(prefix)
+                .or_else(|| part.strip_prefix(prefix.to_ascii_lowercase()))
+                .ok_or_else(|| format!("Rule part {} must start with {}", part, prefix))?;
+            let mut counts = [false; 9];
+            for digit in digits.chars() {
+                match digit.to_digit(10) {
+                    Some(count) if count <= 8 => counts[count as usize] = true,
+                    _ => return Err(format!("Invalid neighbour count in rule: {}", digit)),
+                }
             }
+            Ok(counts)
+        };
+        Ok(Rule {
+            birth: counts(birth, 'B')?,
+            survival: counts(survival, 'S')?,
+        })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = |counts: &[bool; 9]| -> String {
+            (0..9).filter(|&i| counts[i]).map(|i| i.to_string()).collect()
         };
+        write!(f, "B{}/S{}", digits(&self.birth), digits(&self.survival))
+    }
+}
+
+/// Parameters for cellular automata; unset ones follow `complexity`
+#[derive(Debug, Clone, Default)]
+pub struct CellularAutomataParams {
+    /// Birth/survival rule; defaults to `Rule::SMOOTH`
+    pub rule: Option<Rule>,
+    /// Share of cells that start as walls
+    pub fill: Option<f64>,
+    /// Number of generations the rule runs for
+    pub iterations: Option<usize>,
+}
+
+impl CellularAutomataParams {
+    /// Override the parameters named in `params`
+    pub fn apply(&mut self, params: &Params) -> Result<(), String> {
+        params.check_keys("cellular_automata", &["rule", "fill", "iterations"])?;
+        if let Some(rule) = params.get("rule")? {
+            self.rule = Some(rule);
+        }
+        if let Some(fill) = params.get_probability("fill")? {
+            self.fill = Some(fill);
+        }
+        if let Some(iterations) = params.get("iterations")? {
+            self.iterations = Some(iterations);
+        }
+        Ok(())
+    }
+
+    /// The parameters that are set, as they would be written
+    pub fn to_params(&self) -> Params {
+        let mut params = Params::new();
+        if let Some(rule) = &self.rule {
+            params.set("rule", &rule.to_string());
+        }
+        if let Some(fill) = &self.fill {
+            params.set("fill", &fill.to_string());
+        }
+        if let Some(iterations) = &self.iterations {
+            params.set("iterations", &iterations.to_string());
+        }
+        params
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CellularAutomata {
+    pub params: CellularAutomataParams,
+}
+
+impl MazeGenerator for CellularAutomata {
+    fn name(&self) -> &'static str {
+        "cellular_automata"
+    }
+
+    fn description(&self) -> &'static str {
+        "Cave-like open areas grown by cellular automaton rules"
+    }
+
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[
+            GeneratorOption {
+                name: "complexity",
+                description: "Initial wall density and number of smoothing passes",
+            },
+            GeneratorOption {
+                name: "rule",
+                description: "Birth/survival counts of wall neighbours, such as B5678/S5678",
+            },
+            GeneratorOption {
+                name: "fill",
+                description: "Share of cells that start as walls (0.0 to 1.0)",
+            },
+            GeneratorOption {
+                name: "iterations",
+                description: "Number of generations the rule runs for",
+            },
+        ]
+    }
+
+    fn with_params(&self, params: &Params) -> Result<Box<dyn MazeGenerator>, String> {
+        let mut generator = self.clone();
+        generator.params.apply(params)?;
+        Ok(Box::new(generator))
+    }
+
+    fn params(&self) -> Params {
+        self.params.to_params()
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        let width = maze.width();
+        let height = maze.height();
 
         // Initial fill density based on complexity
         // Lower complexity = more open space initially
         // Higher complexity = more walls initially
-        let initial_density = 0.3 + complexity * 0.4; // Range: 0.3 to 0.7
+        let initial_density = self.params.fill.unwrap_or(0.3 + complexity * 0.4); // Range: 0.3 to 0.7
+        let rule = self.params.rule.unwrap_or(Rule::SMOOTH);
 
         // Random fill: mark cells as walls (true) or open (false)
         let mut cells: Vec<Vec<bool>> = (0..height)
@@ -33,7 +180,10 @@ impl MazeGenerator for CellularAutomata {
             .collect();
 
         // Number of CA iterations based on complexity
-        let iterations = (3.0 + complexity * 5.0) as usize; // Range: 3 to 8
+        let iterations = self
+            .params
+            .iterations
+            .unwrap_or((3.0 + complexity * 5.0) as usize); // Range: 3 to 8
 
         // Apply CA smoothing rules
         for _ in 0..iterations {
@@ -64,8 +214,8 @@ impl MazeGenerator for CellularAutomata {
                         }
                     }
 
-                    // CA rule: if 5 or more neighbors are walls, become wall; otherwise become open
-                    new_cells[y as usize][x as usize] = wall_count >= 5;
+                    new_cells[y as usize][x as usize] =
+                        rule.next(cells[y as usize][x as usize], wall_count);
                 }
             }
             cells = new_cells;
@@ -159,8 +309,5 @@ impl MazeGenerator for CellularAutomata {
 
         // Ensure connectivity from start to end
         maze.ensure_connectivity();
-
-        maze
     }
 }
-
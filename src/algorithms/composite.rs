@@ -0,0 +1,425 @@
+use crate::algorithms::{check_maze, GenerateError, MazeGenerator};
+use crate::grid::{self, CellId, Grid};
+use crate::mask::Mask;
+use crate::maze::{Door, Endpoint, Maze, Room};
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+/// How the maze is split between the regions of a composite
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// Four quarters in reading order, taking the regions in turn
+    Quadrants,
+    /// Horizontal bands of equal height, top to bottom
+    Bands,
+    /// Vertical columns of equal width, left to right
+    Columns,
+    /// Each cell belongs to the first region whose zone mask covers it
+    Zones,
+}
+
+impl Layout {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "quadrants" => Some(Layout::Quadrants),
+            "bands" | "rows" => Some(Layout::Bands),
+            "columns" => Some(Layout::Columns),
+            "zones" => Some(Layout::Zones),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Layout::Quadrants => "quadrants",
+            Layout::Bands => "bands",
+            Layout::Columns => "columns",
+            Layout::Zones => "zones",
+        }
+    }
+}
+
+impl FromStr for Layout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Layout::parse(s).ok_or_else(|| {
+            format!("Unknown layout: {} (use quadrants, bands, columns or zones)", s.trim())
+        })
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// One region of a composite and the generator that carves it
+pub struct Region<'a> {
+    pub generator: &'a dyn MazeGenerator,
+    /// Complexity for this region, in place of the composite's own
+    pub complexity: Option<f64>,
+    /// Cells of the region for the zones layout, cell for cell over the maze
+    /// A region without a zone takes every cell no other zone covers.
+    pub zone: Option<Mask>,
+}
+
+impl<'a> Region<'a> {
+    /// A region carved by `generator` with the composite's complexity
+    pub fn new(generator: &'a dyn MazeGenerator) -> Self {
+        Region {
+            generator,
+            complexity: None,
+            zone: None,
+        }
+    }
+}
+
+/// Carves each region of the maze with its own generator, then stitches them
+///
+/// Every region is carved as a maze of its own, so the generators never see
+/// each other's cells. Each pair of neighbouring regions is then joined by
+/// `passages` openings spread along the border they share, which keeps the
+/// whole maze connected and so solvable.
+pub struct Composite<'a> {
+    pub layout: Layout,
+    pub regions: Vec<Region<'a>>,
+    /// Openings between each pair of neighbouring regions, at least 1
+    pub passages: u32,
+}
+
+impl<'a> Composite<'a> {
+    /// Split the maze by `layout`, with one opening between neighbouring regions
+    pub fn new(layout: Layout, regions: Vec<Region<'a>>) -> Self {
+        Composite {
+            layout,
+            regions,
+            passages: 1,
+        }
+    }
+
+    /// Index of the region the cell at (x, y) falls in
+    fn region_at(&self, maze: &Maze, x: u32, y: u32) -> usize {
+        let count = self.regions.len();
+        let (width, height) = (maze.width() as usize, maze.height() as usize);
+        let (x, y) = (x as usize, y as usize);
+        match self.layout {
+            Layout::Quadrants => {
+                let quadrant = 2 * usize::from(y >= height / 2) + usize::from(x >= width / 2);
+                quadrant % count
+            }
+            Layout::Bands => y * count / height,
+            Layout::Columns => x * count / width,
+            Layout::Zones => {
+                let zoned = self.regions.iter().position(|region| {
+                    region
+                        .zone
+                        .as_ref()
+                        .is_some_and(|zone| zone.is_active(x as u32, y as u32))
+                });
+                zoned
+                    .or_else(|| self.regions.iter().position(|region| region.zone.is_none()))
+                    .unwrap_or(count - 1)
+            }
+        }
+    }
+
+    /// Split the active cells into pieces: cells of the same region that
+    /// touch each other. Returns the piece of every cell, or None for
+    /// inactive ones, along with the pieces in reading order.
+    fn pieces(&self, maze: &Maze) -> (Vec<Option<usize>>, Vec<Piece>) {
+        let cell_count = maze.width() as usize * maze.height() as usize;
+        let mut owner = vec![None; cell_count];
+        let mut pieces = Vec::new();
+
+        for (x, y) in maze.active_cells() {
+            if owner[maze.cell_id(x, y)].is_some() {
+                continue;
+            }
+
+            // Flood fill the cells of this region that are reachable from (x, y)
+            let id = pieces.len();
+            let region = self.region_at(maze, x, y);
+            let mut cells = Vec::new();
+            let mut queue = VecDeque::from([(x, y)]);
+            owner[maze.cell_id(x, y)] = Some(id);
+            while let Some((cx, cy)) = queue.pop_front() {
+                cells.push((cx, cy));
+                for (nx, ny) in maze.get_neighbors(cx, cy) {
+                    let next = maze.cell_id(nx, ny);
+                    if owner[next].is_none() && self.region_at(maze, nx, ny) == region {
+                        owner[next] = Some(id);
+                        queue.push_back((nx, ny));
+                    }
+                }
+            }
+            pieces.push(Piece::new(region, &cells, maze));
+        }
+
+        (owner, pieces)
+    }
+
+    /// Pick `passages` walls along the border of each pair of neighbouring pieces
+    /// The border is cut into one stretch per opening and a wall picked at
+    /// random within each, so the openings spread along it. Walls between
+    /// live cells are picked when the border has any; a cell an opening
+    /// reaches is made live, along with a way from it to the live cells.
+    fn stitch(
+        &self,
+        maze: &Maze,
+        owner: &[Option<usize>],
+        live: &mut [bool],
+        rng: &mut ChaCha8Rng,
+    ) -> Vec<(u32, u32, u32, u32)> {
+        // Walls between each pair of pieces, as (x1, y1, x2, y2) in reading order
+        let mut borders: BTreeMap<(usize, usize), Vec<_>> = BTreeMap::new();
+        for (x, y) in maze.active_cells() {
+            let Some(a) = owner[maze.cell_id(x, y)] else {
+                continue;
+            };
+            for (nx, ny) in [(x + 1, y), (x, y + 1)] {
+                if !maze.is_active(nx, ny) {
+                    continue;
+                }
+                match owner[maze.cell_id(nx, ny)] {
+                    Some(b) if b != a => {
+                        borders.entry((a.min(b), a.max(b))).or_default().push((x, y, nx, ny));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut opened = Vec::new();
+        for walls in borders.into_values() {
+            let between_live: Vec<_> = walls
+                .iter()
+                .copied()
+                .filter(|&(x1, y1, x2, y2)| live[maze.cell_id(x1, y1)] && live[maze.cell_id(x2, y2)])
+                .collect();
+            let walls = if between_live.is_empty() { walls } else { between_live };
+
+            let length = walls.len();
+            let openings = (self.passages.max(1) as usize).min(length);
+            for i in 0..openings {
+                let from = i * length / openings;
+                let to = (i + 1) * length / openings;
+                let (x1, y1, x2, y2) = walls[rng.gen_range(from..to)];
+                revive(maze, owner, live, maze.cell_id(x1, y1));
+                revive(maze, owner, live, maze.cell_id(x2, y2));
+                opened.push((x1, y1, x2, y2));
+            }
+        }
+        opened
+    }
+}
+
+/// Make `cell` live, along with the cells of a shortest way inside its piece
+/// to a live cell, so connecting the live cells afterwards reaches it
+fn revive(maze: &Maze, owner: &[Option<usize>], live: &mut [bool], cell: CellId) {
+    let mut came_from = vec![None; live.len()];
+    let mut seen = vec![false; live.len()];
+    let mut queue = VecDeque::from([cell]);
+    seen[cell] = true;
+
+    while let Some(current) = queue.pop_front() {
+        if live[current] {
+            let mut node = current;
+            while let Some(prev) = came_from[node] {
+                live[prev] = true;
+                node = prev;
+            }
+            return;
+        }
+        for next in maze.neighbors(current) {
+            if !seen[next] && owner[next] == owner[cell] {
+                seen[next] = true;
+                came_from[next] = Some(current);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    // The piece has no live cells yet
+    live[cell] = true;
+}
+
+/// Cells of one region that touch each other, as a maze of their own
+struct Piece {
+    region: usize,
+    /// Position of the piece's top-left corner in the whole maze
+    x: u32,
+    y: u32,
+    maze: Maze,
+}
+
+impl Piece {
+    /// A blank maze over the bounding box of `cells`, masked to just those
+    /// cells unless they fill it
+    fn new(region: usize, cells: &[(u32, u32)], whole: &Maze) -> Self {
+        let left = cells.iter().map(|&(x, _)| x).min().expect("a piece has cells");
+        let top = cells.iter().map(|&(_, y)| y).min().expect("a piece has cells");
+        let width = cells.iter().map(|&(x, _)| x).max().expect("a piece has cells") - left + 1;
+        let height = cells.iter().map(|&(_, y)| y).max().expect("a piece has cells") - top + 1;
+
+        let mut maze = if cells.len() == width as usize * height as usize {
+            Maze::new(width, height)
+        } else {
+            let mut mask = Mask::new(width, height);
+            for y in 0..height {
+                for x in 0..width {
+                    mask.set_active(x, y, false);
+                }
+            }
+            for &(x, y) in cells {
+                mask.set_active(x - left, y - top, true);
+            }
+            Maze::with_mask(mask)
+        };
+
+        // Generators that lay out around the endpoints see the ones in this piece
+        let inside = |endpoint: Endpoint| {
+            let (x, y) = (endpoint.x.wrapping_sub(left), endpoint.y.wrapping_sub(top));
+            maze.is_active(x, y).then(|| Endpoint::new(x, y, endpoint.side))
+        };
+        let (start, goal) = (inside(whole.start()), inside(whole.goal()));
+        if let Some(start) = start {
+            maze.set_start(start);
+        }
+        if let Some(goal) = goal {
+            maze.set_goal(goal);
+        }
+
+        Piece {
+            region,
+            x: left,
+            y: top,
+            maze,
+        }
+    }
+
+    /// Id in the whole maze of a cell of the piece
+    fn whole_cell(&self, whole: &Maze, cell: CellId) -> CellId {
+        let (x, y) = self.maze.cell_coords(cell);
+        whole.cell_id(self.x + x, self.y + y)
+    }
+}
+
+impl MazeGenerator for Composite<'_> {
+    fn name(&self) -> &'static str {
+        "composite"
+    }
+
+    fn description(&self) -> &'static str {
+        "Carves each region with its own generator and joins them with a few passages"
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        let (owner, mut pieces) = self.pieces(maze);
+
+        for piece in &mut pieces {
+            let region = &self.regions[piece.region];
+            let complexity = region.complexity.unwrap_or(complexity);
+            if maze.is_recording_events() {
+                piece.maze.record_events();
+            }
+            region.generator.carve(&mut piece.maze, complexity, rng);
+        }
+
+        // Live cells are the ones a passage reaches, and the endpoints. Only
+        // they are joined up, so cells a generator walled off, like the dead
+        // ends a dungeon trims, stay walled off.
+        let mut live = vec![false; maze.cell_count()];
+        for piece in &pieces {
+            for cell in piece.maze.cells() {
+                if !piece.maze.links(cell).is_empty() {
+                    live[piece.whole_cell(maze, cell)] = true;
+                }
+            }
+        }
+        for endpoint in [maze.start(), maze.goal()] {
+            live[maze.cell_id(endpoint.x, endpoint.y)] = true;
+        }
+        let openings = self.stitch(maze, &owner, &mut live, rng);
+
+        let mut rooms = Vec::new();
+        for piece in &mut pieces {
+            // Stitching joins pieces, so the live cells of each piece have to be connected themselves
+            let cells: Vec<CellId> = piece
+                .maze
+                .cells()
+                .into_iter()
+                .filter(|&cell| live[piece.whole_cell(maze, cell)])
+                .collect();
+            grid::connect_cells(&mut piece.maze, &cells);
+
+            if maze.is_recording_events() {
+                // Replaying the piece's events makes the same passages in the whole maze
+                for event in piece.maze.take_events() {
+                    let event = event.map_cells(|cell| piece.whole_cell(maze, cell));
+                    event.apply(maze);
+                }
+            } else {
+                for cell in piece.maze.cells() {
+                    for next in piece.maze.links(cell) {
+                        if next > cell {
+                            let (x1, y1) = piece.maze.cell_coords(cell);
+                            let (x2, y2) = piece.maze.cell_coords(next);
+                            maze.remove_wall(piece.x + x1, piece.y + y1, piece.x + x2, piece.y + y2);
+                        }
+                    }
+                }
+            }
+            rooms.extend(piece.maze.rooms().iter().map(|room| Room {
+                x: piece.x + room.x,
+                y: piece.y + room.y,
+                width: room.width,
+                height: room.height,
+                doors: room
+                    .doors
+                    .iter()
+                    .map(|door| Door {
+                        x: piece.x + door.x,
+                        y: piece.y + door.y,
+                        ..*door
+                    })
+                    .collect(),
+            }));
+        }
+        maze.set_rooms(rooms);
+
+        for (x1, y1, x2, y2) in openings {
+            maze.remove_wall(x1, y1, x2, y2);
+        }
+    }
+
+    fn supports_mask(&self) -> bool {
+        true
+    }
+
+    /// Also checks each piece of the maze against the generator of its region
+    fn check(&self, maze: &Maze) -> Result<(), GenerateError> {
+        check_maze(self, maze)?;
+        if self.regions.is_empty() {
+            return Err(GenerateError::UnsupportedTopology {
+                algorithm: self.name().to_string(),
+                topology: "a layout with no regions".to_string(),
+            });
+        }
+        if maze.wrap().wraps_x() || maze.wrap().wraps_y() {
+            return Err(GenerateError::UnsupportedTopology {
+                algorithm: self.name().to_string(),
+                topology: "wrapped mazes".to_string(),
+            });
+        }
+        let (_, pieces) = self.pieces(maze);
+        for piece in &pieces {
+            self.regions[piece.region].generator.check(&piece.maze)?;
+        }
+        Ok(())
+    }
+}
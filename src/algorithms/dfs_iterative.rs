@@ -1,21 +1,30 @@
-use crate::algorithms::MazeGenerator;
+use crate::algorithms::{GeneratorOption, MazeGenerator};
+use crate::grid::Grid;
 use crate::maze::Maze;
 use rand::Rng;
-use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 
 pub struct DfsIterative;
 
 impl MazeGenerator for DfsIterative {
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze {
-        let mut maze = Maze::new(width, height);
-        let mut rng = match seed {
-            Some(s) => ChaCha8Rng::seed_from_u64(s),
-            None => {
-                let seed = rand::thread_rng().gen();
-                ChaCha8Rng::seed_from_u64(seed)
-            }
-        };
+    fn name(&self) -> &'static str {
+        "dfs_iterative"
+    }
+
+    fn description(&self) -> &'static str {
+        "Depth-first search with an explicit stack"
+    }
+
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[GeneratorOption {
+            name: "complexity",
+            description: "Randomness of the next step",
+        }]
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        let width = maze.width();
+        let height = maze.height();
         let mut stack = Vec::new();
         let mut visited = vec![vec![false; width as usize]; height as usize];
 
@@ -24,6 +33,7 @@ impl MazeGenerator for DfsIterative {
         let start_y = rng.gen_range(0..height);
         stack.push((start_x, start_y));
         visited[start_y as usize][start_x as usize] = true;
+        maze.visit(maze.cell_id(start_x, start_y));
 
         while let Some((x, y)) = stack.pop() {
             // Get unvisited neighbors
@@ -66,11 +76,11 @@ impl MazeGenerator for DfsIterative {
 
                 // Mark neighbor as visited and push onto stack
                 visited[ny as usize][nx as usize] = true;
+                maze.visit(maze.cell_id(nx, ny));
                 stack.push((nx, ny));
+            } else {
+                maze.backtrack(maze.cell_id(x, y));
             }
         }
-
-        maze
     }
 }
-
@@ -1,23 +1,32 @@
-use crate::algorithms::MazeGenerator;
+use crate::algorithms::{GeneratorOption, MazeGenerator};
+use crate::grid::Grid;
 use crate::maze::Maze;
 use rand::Rng;
-use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 
 pub struct DrunkardsWalk;
 
 impl MazeGenerator for DrunkardsWalk {
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze {
-        let mut maze = Maze::new(width, height);
-        let mut rng = match seed {
-            Some(s) => ChaCha8Rng::seed_from_u64(s),
-            None => {
-                let seed = rand::thread_rng().gen();
-                ChaCha8Rng::seed_from_u64(seed)
-            }
-        };
-        let mut carved = HashSet::new();
+    fn name(&self) -> &'static str {
+        "drunkards_walk"
+    }
+
+    fn description(&self) -> &'static str {
+        "Random walk that opens space until enough of the grid is carved; organic caves"
+    }
+
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[GeneratorOption {
+            name: "complexity",
+            description: "Share of the grid carved before the walk stops",
+        }]
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        let width = maze.width();
+        let height = maze.height();
+        let mut carved = BTreeSet::new();
         let total_cells = width * height;
 
         // Density threshold based on complexity
@@ -30,6 +39,7 @@ impl MazeGenerator for DrunkardsWalk {
         let mut current_x = rng.gen_range(0..width);
         let mut current_y = rng.gen_range(0..height);
         carved.insert((current_x, current_y));
+        maze.visit(maze.cell_id(current_x, current_y));
 
         // Random walk until enough area is carved
         let mut steps = 0;
@@ -60,6 +70,7 @@ impl MazeGenerator for DrunkardsWalk {
             // Remove wall and mark as carved
             maze.remove_wall(current_x, current_y, nx, ny);
             carved.insert((nx, ny));
+            maze.visit(maze.cell_id(nx, ny));
             current_x = nx;
             current_y = ny;
             steps += 1;
@@ -131,8 +142,5 @@ impl MazeGenerator for DrunkardsWalk {
 
         // Ensure connectivity from start to end
         maze.ensure_connectivity();
-
-        maze
     }
 }
-
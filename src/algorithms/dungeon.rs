@@ -0,0 +1,423 @@
+use crate::algorithms::{check_maze, GenerateError, GeneratorOption, MazeGenerator, Params};
+use crate::grid::{CellId, Grid};
+use crate::maze::{Door, Maze, Room};
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+/// Parameters for the dungeon generator; unset ones use the defaults listed
+#[derive(Debug, Clone, Default)]
+pub struct DungeonParams {
+    /// Rooms to place; fewer are placed when no free space is found
+    pub rooms: Option<usize>,
+    /// Shortest side of a room, in cells, at least 1
+    pub min_room: Option<u32>,
+    /// Longest side of a room, in cells, at least `min_room`
+    pub max_room: Option<u32>,
+    /// Most doors a room gets, at least 1; every room gets at least one
+    pub doors: Option<u32>,
+    /// Share of the dead-end corridors trimmed back
+    pub trim: Option<f64>,
+}
+
+impl DungeonParams {
+    /// Override the parameters named in `params`
+    pub fn apply(&mut self, params: &Params) -> Result<(), String> {
+        params.check_keys("dungeon", &["rooms", "min_room", "max_room", "doors", "trim"])?;
+        if let Some(rooms) = params.get("rooms")? {
+            self.rooms = Some(rooms);
+        }
+        if let Some(min_room) = params.get("min_room")? {
+            self.min_room = Some(min_room);
+        }
+        if let Some(max_room) = params.get("max_room")? {
+            self.max_room = Some(max_room);
+        }
+        if let Some(doors) = params.get("doors")? {
+            self.doors = Some(doors);
+        }
+        if let Some(trim) = params.get_probability("trim")? {
+            self.trim = Some(trim);
+        }
+        for (key, value) in [
+            ("min_room", self.min_room),
+            ("max_room", self.max_room),
+            ("doors", self.doors),
+        ] {
+            if value == Some(0) {
+                return Err(format!("Parameter {} must be at least 1, got: 0", key));
+            }
+        }
+        if let (Some(min_room), Some(max_room)) = (self.min_room, self.max_room) {
+            if max_room < min_room {
+                return Err(format!(
+                    "Parameter max_room must be at least min_room ({}), got: {}",
+                    min_room, max_room
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The parameters that are set, as they would be written
+    pub fn to_params(&self) -> Params {
+        let mut params = Params::new();
+        if let Some(rooms) = &self.rooms {
+            params.set("rooms", &rooms.to_string());
+        }
+        if let Some(min_room) = &self.min_room {
+            params.set("min_room", &min_room.to_string());
+        }
+        if let Some(max_room) = &self.max_room {
+            params.set("max_room", &max_room.to_string());
+        }
+        if let Some(doors) = &self.doors {
+            params.set("doors", &doors.to_string());
+        }
+        if let Some(trim) = &self.trim {
+            params.set("trim", &trim.to_string());
+        }
+        params
+    }
+}
+
+/// Rectangular rooms joined by winding corridors
+///
+/// Rooms are dropped at random wherever they keep a cell of space from every
+/// other room, and the space left over is filled with corridors grown depth
+/// first. Each room and each stretch of corridor is a region; walls between
+/// regions are opened in random order until all of them are joined, and
+/// rooms then get extra doors. Dead-end corridors are trimmed back last.
+/// The rooms and their doors are kept on the maze, see `Maze::rooms`.
+#[derive(Debug, Clone, Default)]
+pub struct Dungeon {
+    pub params: DungeonParams,
+}
+
+impl MazeGenerator for Dungeon {
+    fn name(&self) -> &'static str {
+        "dungeon"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["rooms_and_corridors"]
+    }
+
+    fn description(&self) -> &'static str {
+        "Rectangular rooms joined by corridors, with dead ends trimmed away"
+    }
+
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[
+            GeneratorOption {
+                name: "complexity",
+                description: "How winding the corridors are; low values give long straight runs",
+            },
+            GeneratorOption {
+                name: "rooms",
+                description: "Rooms to place; fewer fit in a crowded maze (default one per 60 cells)",
+            },
+            GeneratorOption {
+                name: "min_room",
+                description: "Shortest side of a room in cells (default 3)",
+            },
+            GeneratorOption {
+                name: "max_room",
+                description: "Longest side of a room in cells (default 7)",
+            },
+            GeneratorOption {
+                name: "doors",
+                description: "Most doors per room; each room gets between 1 and this many (default 2)",
+            },
+            GeneratorOption {
+                name: "trim",
+                description: "Share of the dead-end corridors trimmed back (0.0 to 1.0, default 1.0)",
+            },
+        ]
+    }
+
+    fn with_params(&self, params: &Params) -> Result<Box<dyn MazeGenerator>, String> {
+        let mut generator = self.clone();
+        generator.params.apply(params)?;
+        Ok(Box::new(generator))
+    }
+
+    fn params(&self) -> Params {
+        self.params.to_params()
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        // A side left unset follows the one that is set, so min_room = 9 alone works
+        let min_room = self
+            .params
+            .min_room
+            .unwrap_or(self.params.max_room.map_or(3, |max| max.min(3)));
+        let max_room = self.params.max_room.unwrap_or(min_room.max(7));
+        let count = self.params.rooms.unwrap_or((maze.active_count() / 60).max(1));
+        let doors = self.params.doors.unwrap_or(2);
+        let trim = self.params.trim.unwrap_or(1.0);
+
+        // Rooms are the first regions, numbered as in `rooms`
+        let mut rooms = place_rooms(maze, count, min_room, max_room, rng);
+        let mut region: Vec<Option<usize>> = vec![None; maze.cell_count()];
+        for (i, room) in rooms.iter().enumerate() {
+            for y in room.y..room.y + room.height {
+                for x in room.x..room.x + room.width {
+                    region[maze.cell_id(x, y)] = Some(i);
+                    if x + 1 < room.x + room.width {
+                        maze.remove_wall(x, y, x + 1, y);
+                    }
+                    if y + 1 < room.y + room.height {
+                        maze.remove_wall(x, y, x, y + 1);
+                    }
+                }
+            }
+        }
+
+        // Every stretch of free space becomes a corridor region of its own
+        let mut regions = rooms.len();
+        for cell in maze.cells() {
+            if region[cell].is_none() {
+                grow_corridor(maze, cell, regions, &mut region, complexity, rng);
+                regions += 1;
+            }
+        }
+
+        connect_regions(maze, &mut rooms, &region, regions, doors, rng);
+
+        // Wall off dead ends back to the nearest junction, room or endpoint
+        let start = maze.start();
+        let goal = maze.goal();
+        let keep = [maze.cell_id(start.x, start.y), maze.cell_id(goal.x, goal.y)];
+        let in_room = |cell: CellId| region[cell].is_some_and(|r| r < rooms.len());
+        let dead_ends: Vec<CellId> = maze
+            .cells()
+            .into_iter()
+            .filter(|&cell| !in_room(cell) && !keep.contains(&cell) && maze.links(cell).len() == 1)
+            .collect();
+        for mut cell in dead_ends {
+            if !rng.gen_bool(trim) {
+                continue;
+            }
+            while !in_room(cell) && !keep.contains(&cell) {
+                match maze.links(cell).as_slice() {
+                    &[only] => {
+                        maze.unlink(cell, only);
+                        cell = only;
+                    }
+                    _ => break,
+                }
+            }
+        }
+
+        // A door whose corridor was trimmed away opens onto nothing
+        for room in &mut rooms {
+            room.doors.retain(|door| {
+                let cell = maze.cell_id(door.x, door.y);
+                maze.links(cell).into_iter().any(|n| {
+                    let (nx, ny) = maze.cell_coords(n);
+                    maze.side_facing(door.x, door.y, nx, ny) == Some(door.side)
+                })
+            });
+        }
+
+        maze.ensure_connectivity();
+        maze.set_rooms(rooms);
+    }
+
+    fn supports_mask(&self) -> bool {
+        true
+    }
+
+    /// A min_room that was set must also fit the maze
+    fn check(&self, maze: &Maze) -> Result<(), GenerateError> {
+        check_maze(self, maze)?;
+        let (width, height) = (maze.width(), maze.height());
+        if let Some(min_room) = self.params.min_room.filter(|&min| min > width || min > height) {
+            return Err(GenerateError::UnsupportedTopology {
+                algorithm: self.name().to_string(),
+                topology: format!(
+                    "rooms of at least {} cells a side in a {}x{} maze",
+                    min_room, width, height
+                ),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Drop rooms at random where they fit, a few tries per room
+/// Rooms lie on active cells only and keep a cell of space between them.
+fn place_rooms(maze: &Maze, count: usize, min: u32, max: u32, rng: &mut ChaCha8Rng) -> Vec<Room> {
+    let (width, height) = (maze.width(), maze.height());
+    let mut rooms: Vec<Room> = Vec::new();
+    if min > width || min > height {
+        return rooms;
+    }
+
+    let mut taken = vec![false; maze.cell_count()];
+    for _ in 0..count * 10 {
+        if rooms.len() >= count {
+            break;
+        }
+        let room_width = rng.gen_range(min..=max.min(width));
+        let room_height = rng.gen_range(min..=max.min(height));
+        let x = rng.gen_range(0..=width - room_width);
+        let y = rng.gen_range(0..=height - room_height);
+
+        // The room and the ring of cells around it must be free
+        let (left, top) = (x.saturating_sub(1), y.saturating_sub(1));
+        let (right, bottom) = ((x + room_width + 1).min(width), (y + room_height + 1).min(height));
+        let apart = (top..bottom).all(|y| (left..right).all(|x| !taken[maze.cell_id(x, y)]));
+        let active = (y..y + room_height).all(|y| (x..x + room_width).all(|x| maze.is_active(x, y)));
+        if apart && active {
+            for y in y..y + room_height {
+                for x in x..x + room_width {
+                    taken[maze.cell_id(x, y)] = true;
+                }
+            }
+            rooms.push(Room::new(x, y, room_width, room_height));
+        }
+    }
+    rooms
+}
+
+/// Fill the free cells reachable from `start` with a corridor maze, depth first
+/// Below a complexity of 1.0 a corridor tends to run straight on.
+fn grow_corridor(
+    maze: &mut Maze,
+    start: CellId,
+    id: usize,
+    region: &mut [Option<usize>],
+    complexity: f64,
+    rng: &mut ChaCha8Rng,
+) {
+    region[start] = Some(id);
+    maze.visit(start);
+    // Each cell with the cell it was entered from
+    let mut stack: Vec<(CellId, Option<CellId>)> = vec![(start, None)];
+
+    while let Some(&(cell, from)) = stack.last() {
+        let free: Vec<CellId> = maze
+            .neighbors(cell)
+            .into_iter()
+            .filter(|&n| region[n].is_none())
+            .collect();
+        if free.is_empty() {
+            maze.backtrack(cell);
+            stack.pop();
+            continue;
+        }
+
+        let (x, y) = maze.cell_coords(cell);
+        let ahead = from.and_then(|from| {
+            let (fx, fy) = maze.cell_coords(from);
+            free.iter().copied().find(|&n| {
+                let (nx, ny) = maze.cell_coords(n);
+                nx as i64 - x as i64 == x as i64 - fx as i64
+                    && ny as i64 - y as i64 == y as i64 - fy as i64
+            })
+        });
+        let next = match ahead {
+            Some(next) if rng.gen::<f64>() >= complexity => next,
+            _ => free[rng.gen_range(0..free.len())],
+        };
+
+        maze.link(cell, next);
+        region[next] = Some(id);
+        maze.visit(next);
+        stack.push((next, Some(cell)));
+    }
+}
+
+/// Open walls between regions until all of them are joined, then give each
+/// room between one and `doors` doors in all
+fn connect_regions(
+    maze: &mut Maze,
+    rooms: &mut [Room],
+    region: &[Option<usize>],
+    regions: usize,
+    doors: u32,
+    rng: &mut ChaCha8Rng,
+) {
+    fn find(parent: &mut [usize], mut r: usize) -> usize {
+        while parent[r] != r {
+            parent[r] = parent[parent[r]];
+            r = parent[r];
+        }
+        r
+    }
+
+    // Walls between two different regions, each once, in random order
+    let mut connectors = Vec::new();
+    for cell in maze.cells() {
+        for neighbor in maze.neighbors(cell) {
+            if neighbor > cell && region[cell] != region[neighbor] {
+                connectors.push((cell, neighbor));
+            }
+        }
+    }
+    for i in 0..connectors.len() {
+        let j = rng.gen_range(i..connectors.len());
+        connectors.swap(i, j);
+    }
+
+    let room_count = rooms.len();
+    let room_of = |cell: CellId| region[cell].filter(|&r| r < room_count);
+    let mut parent: Vec<usize> = (0..regions).collect();
+    let mut opened = vec![false; connectors.len()];
+    for (i, &(a, b)) in connectors.iter().enumerate() {
+        let (Some(ra), Some(rb)) = (region[a], region[b]) else {
+            continue;
+        };
+        let (ra, rb) = (find(&mut parent, ra), find(&mut parent, rb));
+        if ra != rb {
+            maze.link(a, b);
+            parent[ra] = rb;
+            opened[i] = true;
+        }
+    }
+
+    // Note the doors the joins made, and each room's walls to other regions
+    let mut walls: Vec<Vec<(CellId, CellId)>> = vec![Vec::new(); room_count];
+    for (i, &(a, b)) in connectors.iter().enumerate() {
+        for (inner, outer) in [(a, b), (b, a)] {
+            if let Some(r) = room_of(inner) {
+                if opened[i] {
+                    rooms[r].doors.push(door(maze, inner, outer));
+                } else {
+                    walls[r].push((inner, outer));
+                }
+            }
+        }
+    }
+
+    // Then open more, up to each room's share
+    for (room, walls) in rooms.iter_mut().zip(walls) {
+        let wanted = rng.gen_range(1..=doors) as usize;
+        for (inner, outer) in walls {
+            if room.doors.len() >= wanted {
+                break;
+            }
+            // Keep doors apart, so two never open side by side
+            let (x, y) = maze.cell_coords(inner);
+            let crowded = room
+                .doors
+                .iter()
+                .any(|door| door.x.abs_diff(x) + door.y.abs_diff(y) <= 1);
+            if !crowded {
+                maze.link(inner, outer);
+                room.doors.push(door(maze, inner, outer));
+            }
+        }
+    }
+}
+
+/// The door in `inner` that opens onto its neighbour `outer`
+fn door(maze: &Maze, inner: CellId, outer: CellId) -> Door {
+    let (x, y) = maze.cell_coords(inner);
+    let (nx, ny) = maze.cell_coords(outer);
+    let side = maze
+        .side_facing(x, y, nx, ny)
+        .expect("doors open onto a neighbouring cell");
+    Door { x, y, side }
+}
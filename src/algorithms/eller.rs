@@ -1,22 +1,34 @@
-use crate::algorithms::MazeGenerator;
+use crate::algorithms::{GeneratorOption, MazeGenerator};
 use crate::maze::Maze;
 use rand::Rng;
-use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 pub struct Eller;
 
 impl MazeGenerator for Eller {
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze {
-        let mut maze = Maze::new(width, height);
-        let mut rng = match seed {
-            Some(s) => ChaCha8Rng::seed_from_u64(s),
-            None => {
-                let seed = rand::thread_rng().gen();
-                ChaCha8Rng::seed_from_u64(seed)
-            }
-        };
+    fn name(&self) -> &'static str {
+        "eller"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ellers"]
+    }
+
+    fn description(&self) -> &'static str {
+        "Row by row with set tracking; constant memory per row"
+    }
+
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[GeneratorOption {
+            name: "complexity",
+            description: "Randomness of merging cells in a row and of the connections down",
+        }]
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        let width = maze.width();
+        let height = maze.height();
 
         // Union-Find for tracking sets
         struct UnionFind {
@@ -80,15 +92,15 @@ impl MazeGenerator for Eller {
             // Create vertical connections to next row (if not last row)
             if y < height - 1 {
                 // Group cells by their set
-                let mut set_to_cells: HashMap<usize, Vec<u32>> = HashMap::new();
+                let mut set_to_cells: BTreeMap<usize, Vec<u32>> = BTreeMap::new();
                 for x in 0..width {
                     let idx = (y * width + x) as usize;
                     let set = uf.find(idx);
-                    set_to_cells.entry(set).or_insert_with(Vec::new).push(x);
+                    set_to_cells.entry(set).or_default().push(x);
                 }
 
                 // For each set, create at least one vertical connection
-                for (_set, cells) in &set_to_cells {
+                for cells in set_to_cells.values() {
                     // Number of vertical connections based on complexity
                     // At complexity 0.0: exactly 1 connection per set
                     // At complexity 1.0: more connections (up to all cells in set)
@@ -117,8 +129,7 @@ impl MazeGenerator for Eller {
                     }
 
                     // Create vertical connections
-                    for i in 0..num_connections.min(cells_to_connect.len()) {
-                        let x = cells_to_connect[i];
+                    for &x in cells_to_connect.iter().take(num_connections) {
                         let idx1 = (y * width + x) as usize;
                         let idx2 = ((y + 1) * width + x) as usize;
                         maze.remove_wall(x, y, x, y + 1);
@@ -133,8 +144,5 @@ impl MazeGenerator for Eller {
                 }
             }
         }
-
-        maze
     }
 }
-
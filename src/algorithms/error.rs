@@ -0,0 +1,44 @@
+use std::error::Error;
+use std::fmt;
+
+/// Why a maze could not be generated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenerateError {
+    /// The maze has no cells to carve, e.g. a width or height of 0
+    InvalidDimensions { width: u32, height: u32 },
+    /// The algorithm cannot carve this kind of maze, e.g. a masked or hex one
+    UnsupportedTopology { algorithm: String, topology: String },
+    /// The maze has more cells than the algorithm can carve safely
+    ResourceLimit {
+        algorithm: String,
+        cells: usize,
+        max_cells: usize,
+    },
+}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerateError::InvalidDimensions { width, height } if *width == 0 || *height == 0 => {
+                write!(f, "Maze dimensions must be at least 1x1, got {}x{}", width, height)
+            }
+            GenerateError::InvalidDimensions { width, height } => {
+                write!(f, "Maze of {}x{} has no active cells to carve", width, height)
+            }
+            GenerateError::UnsupportedTopology { algorithm, topology } => {
+                write!(f, "Algorithm {} does not support {}", algorithm, topology)
+            }
+            GenerateError::ResourceLimit {
+                algorithm,
+                cells,
+                max_cells,
+            } => write!(
+                f,
+                "Algorithm {} can carve at most {} cells, got {}",
+                algorithm, max_cells, cells
+            ),
+        }
+    }
+}
+
+impl Error for GenerateError {}
@@ -1,85 +1,289 @@
-use crate::algorithms::MazeGenerator;
+use crate::algorithms::{pick_weighted, GenerateError, GeneratorOption, MazeGenerator, Params};
+use crate::grid::{CellId, Grid};
 use crate::maze::Maze;
 use rand::Rng;
-use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
 
-pub struct GrowingTree;
+/// How the next active cell is picked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    /// The most recently added cell, like recursive backtracking
+    Newest,
+    /// Any active cell, like Prim
+    Random,
+    /// The first cell still active, growing outward in rings
+    Oldest,
+}
 
-impl MazeGenerator for GrowingTree {
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze {
-        let mut maze = Maze::new(width, height);
-        let mut rng = match seed {
-            Some(s) => ChaCha8Rng::seed_from_u64(s),
-            None => {
-                let seed = rand::thread_rng().gen();
-                ChaCha8Rng::seed_from_u64(seed)
+impl Selection {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "newest" | "last" => Some(Selection::Newest),
+            "random" => Some(Selection::Random),
+            "oldest" | "first" => Some(Selection::Oldest),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Selection::Newest => "newest",
+            Selection::Random => "random",
+            Selection::Oldest => "oldest",
+        }
+    }
+}
+
+/// Weighted choice between selections, written as "newest:75,random:25"
+/// A selection without a weight counts as weight 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectionMix {
+    weights: Vec<(Selection, u32)>,
+}
+
+impl SelectionMix {
+    /// The mix `complexity` picks: newest, random, oldest, then newest and oldest evenly
+    pub fn from_complexity(complexity: f64) -> Self {
+        let weights = if complexity < 0.25 {
+            vec![(Selection::Newest, 1)]
+        } else if complexity < 0.5 {
+            vec![(Selection::Random, 1)]
+        } else if complexity < 0.75 {
+            vec![(Selection::Oldest, 1)]
+        } else {
+            vec![(Selection::Newest, 1), (Selection::Oldest, 1)]
+        };
+        SelectionMix { weights }
+    }
+
+    /// Index into the active list to grow from next
+    /// A single selection draws nothing from `rng` to make its choice.
+    fn pick(&self, len: usize, rng: &mut ChaCha8Rng) -> usize {
+        let selection = match self.weights.as_slice() {
+            [(selection, _)] => *selection,
+            weights => {
+                let total: u32 = weights.iter().map(|&(_, weight)| weight).sum();
+                let mut roll = rng.gen::<f64>() * total as f64;
+                weights
+                    .iter()
+                    .find(|&&(_, weight)| {
+                        roll -= weight as f64;
+                        roll < 0.0
+                    })
+                    .or(weights.last())
+                    .map(|&(selection, _)| selection)
+                    .unwrap_or(Selection::Newest)
             }
         };
-        let mut active = Vec::new();
-        let mut visited = HashSet::new();
-
-        // Start at a random cell
-        let start_x = rng.gen_range(0..width);
-        let start_y = rng.gen_range(0..height);
-        active.push((start_x, start_y));
-        visited.insert((start_x, start_y));
-
-        while !active.is_empty() {
-            // Select active cell based on policy (determined by complexity)
-            let current_idx = if complexity < 0.25 {
-                // Newest (stack-like, DFS-like): always take last
-                active.len() - 1
-            } else if complexity < 0.5 {
-                // Random: pick random active cell
-                rng.gen_range(0..active.len())
-            } else if complexity < 0.75 {
-                // Oldest (queue-like, BFS-like): always take first
-                0
-            } else {
-                // Mixed: weighted random between newest and oldest
-                if rng.gen::<f64>() < 0.5 {
-                    active.len() - 1 // newest
-                } else {
-                    0 // oldest
+
+        match selection {
+            Selection::Newest => len - 1,
+            Selection::Random => rng.gen_range(0..len),
+            Selection::Oldest => 0,
+        }
+    }
+}
+
+impl FromStr for SelectionMix {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = Vec::new();
+        for part in s.split(',') {
+            let (name, weight) = match part.split_once(':') {
+                Some((name, weight)) => {
+                    let weight = weight
+                        .trim()
+                        .parse()
+                        .map_err(|e| format!("Invalid weight in {}: {}", part.trim(), e))?;
+                    (name, weight)
                 }
+                None => (part, 1),
             };
-
-            let (x, y) = active[current_idx];
-
-            // Get unvisited neighbors
-            let neighbors: Vec<(u32, u32)> = maze
-                .get_neighbors(x, y)
-                .into_iter()
-                .filter(|&(nx, ny)| !visited.contains(&(nx, ny)))
-                .collect();
-
-            if !neighbors.is_empty() {
-                // Choose neighbor (random selection, complexity affects this)
-                let next_idx = if neighbors.len() == 1 {
-                    0
-                } else if complexity < 0.1 {
-                    // Very low complexity: prefer first neighbor
-                    0
-                } else {
-                    rng.gen_range(0..neighbors.len())
-                };
-                let (nx, ny) = neighbors[next_idx];
-
-                // Remove wall between current and chosen neighbor
-                maze.remove_wall(x, y, nx, ny);
-
-                // Mark neighbor as visited and add to active set
-                visited.insert((nx, ny));
-                active.push((nx, ny));
-            } else {
-                // No unvisited neighbors, remove from active set
-                active.remove(current_idx);
+            let selection = Selection::parse(name)
+                .ok_or_else(|| format!("Unknown selection: {}", name.trim()))?;
+            if weight > 0 {
+                weights.push((selection, weight));
             }
         }
+        if weights.is_empty() {
+            return Err("Selection mix needs at least one positive weight".to_string());
+        }
+        Ok(SelectionMix { weights })
+    }
+}
 
-        maze
+impl fmt::Display for SelectionMix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self
+            .weights
+            .iter()
+            .map(|(selection, weight)| format!("{}:{}", selection.name(), weight))
+            .collect();
+        write!(f, "{}", parts.join(","))
     }
 }
 
+/// Parameters for growing tree; unset ones follow `complexity`
+#[derive(Debug, Clone, Default)]
+pub struct GrowingTreeParams {
+    pub mix: Option<SelectionMix>,
+}
+
+impl GrowingTreeParams {
+    /// Override the parameters named in `params`
+    pub fn apply(&mut self, params: &Params) -> Result<(), String> {
+        params.check_keys("growing_tree", &["mix"])?;
+        if let Some(mix) = params.get("mix")? {
+            self.mix = Some(mix);
+        }
+        Ok(())
+    }
+
+    /// The parameters that are set, as they would be written
+    pub fn to_params(&self) -> Params {
+        let mut params = Params::new();
+        if let Some(mix) = &self.mix {
+            params.set("mix", &mix.to_string());
+        }
+        params
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GrowingTree {
+    pub params: GrowingTreeParams,
+}
+
+impl MazeGenerator for GrowingTree {
+    fn name(&self) -> &'static str {
+        "growing_tree"
+    }
+
+    fn description(&self) -> &'static str {
+        "Grows from a list of active cells; behaves like backtracking or Prim depending on the pick"
+    }
+
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[
+            GeneratorOption {
+                name: "complexity",
+                description: "Which active cell is grown: newest, random, oldest or a mix",
+            },
+            GeneratorOption {
+                name: "mix",
+                description: "Weighted selections such as newest:75,random:25 (newest, random, oldest)",
+            },
+        ]
+    }
+
+    fn with_params(&self, params: &Params) -> Result<Box<dyn MazeGenerator>, String> {
+        let mut generator = self.clone();
+        generator.params.apply(params)?;
+        Ok(Box::new(generator))
+    }
+
+    fn params(&self) -> Params {
+        self.params.to_params()
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        carve_tree(maze, self.params.mix.as_ref(), complexity, rng);
+    }
+
+    fn carve_grid(
+        &self,
+        grid: &mut dyn Grid,
+        complexity: f64,
+        rng: &mut ChaCha8Rng,
+    ) -> Result<(), GenerateError> {
+        carve_tree(grid, self.params.mix.as_ref(), complexity, rng);
+        Ok(())
+    }
+
+    fn supports_mask(&self) -> bool {
+        true
+    }
+
+    fn supports_grids(&self) -> bool {
+        true
+    }
+
+    fn supports_weights(&self) -> bool {
+        true
+    }
+
+    fn supports_density(&self) -> bool {
+        true
+    }
+}
+
+/// Grow a tree with `mix`, or with the mix the complexity picks if None
+fn carve_tree(
+    grid: &mut dyn Grid,
+    mix: Option<&SelectionMix>,
+    complexity: f64,
+    rng: &mut ChaCha8Rng,
+) {
+    let default_mix = SelectionMix::from_complexity(complexity);
+    let mut active = Vec::new();
+    let mut visited = HashSet::new();
+
+    // Start at a random cell
+    let start = grid.random_cell(rng);
+    active.push(start);
+    visited.insert(start);
+    grid.visit(start);
+
+    while !active.is_empty() {
+        // A density map picks the mix from the complexity where the maze last grew
+        let newest = active[active.len() - 1];
+        let current_idx = match (mix, grid.complexity(newest)) {
+            (Some(mix), _) => mix.pick(active.len(), rng),
+            (None, Some(local)) => SelectionMix::from_complexity(local).pick(active.len(), rng),
+            (None, None) => default_mix.pick(active.len(), rng),
+        };
+
+        let current = active[current_idx];
+        let complexity = grid.complexity(current).unwrap_or(complexity);
+
+        // Get unvisited neighbors
+        let neighbors: Vec<CellId> = grid
+            .neighbors(current)
+            .into_iter()
+            .filter(|n| !visited.contains(n))
+            .collect();
+
+        if !neighbors.is_empty() {
+            // Choose neighbor (random selection, complexity affects this)
+            let weighted = pick_weighted(&*grid, neighbors.iter().map(|&n| (current, n)), rng);
+            let next_idx = if let Some(idx) = weighted {
+                // A weighted grid, e.g. a directional bias, decides instead
+                idx
+            } else if neighbors.len() == 1 {
+                0
+            } else if complexity < 0.1 {
+                // Very low complexity: prefer first neighbor
+                0
+            } else {
+                rng.gen_range(0..neighbors.len())
+            };
+            let next = neighbors[next_idx];
+
+            // Remove wall between current and chosen neighbor
+            grid.link(current, next);
+
+            // Mark neighbor as visited and add to active set
+            visited.insert(next);
+            grid.visit(next);
+            active.push(next);
+        } else {
+            // No unvisited neighbors, remove from active set
+            grid.backtrack(current);
+            active.remove(current_idx);
+        }
+    }
+}
@@ -1,172 +1,266 @@
-use crate::algorithms::MazeGenerator;
-use crate::maze::Maze;
+use crate::algorithms::{
+    check_maze, GenerateError, GeneratorOption, MazeGenerator, Params, RecursiveBacktracking,
+};
+use crate::grid::{CellId, Grid};
+use crate::maze::{Endpoint, Maze, Side};
 use rand::Rng;
-use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 
-pub struct Hamiltonian;
+/// Parameters for the Hamiltonian generator
+#[derive(Debug, Clone, Default)]
+pub struct HamiltonianParams {
+    /// Close the corridor into a loop instead of ending it at the entrance
+    pub cycle: Option<bool>,
+}
+
+impl HamiltonianParams {
+    /// Override the parameters named in `params`
+    pub fn apply(&mut self, params: &Params) -> Result<(), String> {
+        params.check_keys("hamiltonian", &["cycle"])?;
+        if let Some(cycle) = params.get("cycle")? {
+            self.cycle = Some(cycle);
+        }
+        Ok(())
+    }
+
+    /// The parameters that are set, as they would be written
+    pub fn to_params(&self) -> Params {
+        let mut params = Params::new();
+        if let Some(cycle) = &self.cycle {
+            params.set("cycle", &cycle.to_string());
+        }
+        params
+    }
+}
+
+/// A single corridor through every cell, built from 2x2 blocks
+///
+/// A spanning tree over the blocks is carved first. Each block starts as a
+/// loop around its four cells, and the loops of blocks joined in the tree are
+/// merged, leaving one loop through every cell. An odd row or column is
+/// folded in afterwards, and unless a cycle is asked for the loop is opened
+/// at the entrance and the exit moves to the other side of the opening, so
+/// the solution visits every cell. Everything is linear in the number of cells.
+#[derive(Debug, Clone, Default)]
+pub struct Hamiltonian {
+    pub params: HamiltonianParams,
+}
 
 impl MazeGenerator for Hamiltonian {
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze {
-        let mut maze = Maze::new(width, height);
-        let mut rng = match seed {
-            Some(s) => ChaCha8Rng::seed_from_u64(s),
-            None => {
-                let seed = rand::thread_rng().gen();
-                ChaCha8Rng::seed_from_u64(seed)
-            }
-        };
+    fn name(&self) -> &'static str {
+        "hamiltonian"
+    }
 
-        // Generate a Hamiltonian cycle/path using backtracking
-        let mut path = Vec::new();
-        let mut visited = vec![vec![false; width as usize]; height as usize];
-        let total_cells = width * height;
-
-        // Start at (0, 0)
-        let start_x = 0;
-        let start_y = 0;
-
-        // Try to find a Hamiltonian path using backtracking
-        fn find_hamiltonian_path(
-            maze: &Maze,
-            rng: &mut ChaCha8Rng,
-            x: u32,
-            y: u32,
-            path: &mut Vec<(u32, u32)>,
-            visited: &mut Vec<Vec<bool>>,
-            width: u32,
-            height: u32,
-            complexity: f64,
-        ) -> bool {
-            path.push((x, y));
-            visited[y as usize][x as usize] = true;
-
-            if path.len() == (width * height) as usize {
-                return true; // Found Hamiltonian path
-            }
+    fn description(&self) -> &'static str {
+        "One corridor that passes through every cell exactly once, with no branches"
+    }
 
-            // Get unvisited neighbors
-            let mut neighbors: Vec<(u32, u32)> = maze
-                .get_neighbors(x, y)
-                .into_iter()
-                .filter(|&(nx, ny)| !visited[ny as usize][nx as usize])
-                .collect();
-
-            // Shuffle neighbors based on complexity
-            if complexity > 0.0 && neighbors.len() > 1 {
-                for i in 0..neighbors.len() {
-                    let j = rng.gen_range(i..neighbors.len());
-                    neighbors.swap(i, j);
-                }
-            }
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[
+            GeneratorOption {
+                name: "complexity",
+                description: "How winding the corridor is; low values give long straight sweeps",
+            },
+            GeneratorOption {
+                name: "cycle",
+                description: "Close the corridor into a loop (true or false); needs an even side",
+            },
+        ]
+    }
 
-            // Try each neighbor
-            for (nx, ny) in neighbors {
-                if find_hamiltonian_path(maze, rng, nx, ny, path, visited, width, height, complexity)
-                {
-                    return true;
-                }
-            }
+    fn with_params(&self, params: &Params) -> Result<Box<dyn MazeGenerator>, String> {
+        let mut generator = self.clone();
+        generator.params.apply(params)?;
+        Ok(Box::new(generator))
+    }
 
-            // Backtrack
-            path.pop();
-            visited[y as usize][x as usize] = false;
-            false
+    fn params(&self) -> Params {
+        self.params.to_params()
+    }
+
+    /// A cycle also needs both sides to be at least 2 and one of them even
+    fn check(&self, maze: &Maze) -> Result<(), GenerateError> {
+        check_maze(self, maze)?;
+        let (width, height) = (maze.width(), maze.height());
+        if self.params.cycle == Some(true) && !cycle_fits(width, height) {
+            return Err(GenerateError::UnsupportedTopology {
+                algorithm: self.name().to_string(),
+                topology: format!(
+                    "a cycle on {}x{} cells; both sides must be at least 2 and one of them even",
+                    width, height
+                ),
+            });
         }
+        Ok(())
+    }
 
-        // Try to find Hamiltonian path (may fail for large mazes, so we'll have a fallback)
-        let found = find_hamiltonian_path(
-            &maze,
-            &mut rng,
-            start_x,
-            start_y,
-            &mut path,
-            &mut visited,
-            width,
-            height,
-            complexity,
-        );
-
-        if !found || path.len() < (total_cells * 3 / 4) as usize {
-            // Fallback: create a spanning tree and extend it to cover most cells
-            path.clear();
-            visited = vec![vec![false; width as usize]; height as usize];
-            let mut stack = vec![(start_x, start_y)];
-            visited[start_y as usize][start_x as usize] = true;
-
-            while let Some((x, y)) = stack.pop() {
-                path.push((x, y));
-                let mut neighbors: Vec<(u32, u32)> = maze
-                    .get_neighbors(x, y)
-                    .into_iter()
-                    .filter(|&(nx, ny)| !visited[ny as usize][nx as usize])
-                    .collect();
-
-                if complexity > 0.0 && neighbors.len() > 1 {
-                    for i in 0..neighbors.len() {
-                        let j = rng.gen_range(i..neighbors.len());
-                        neighbors.swap(i, j);
-                    }
-                }
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        let (width, height) = (maze.width(), maze.height());
 
-                for (nx, ny) in neighbors {
-                    visited[ny as usize][nx as usize] = true;
-                    stack.push((nx, ny));
+        if width == 1 || height == 1 {
+            // A single row or column is already a corridor
+            for y in 0..height {
+                for x in 0..width {
+                    if x + 1 < width {
+                        maze.remove_wall(x, y, x + 1, y);
+                    }
+                    if y + 1 < height {
+                        maze.remove_wall(x, y, x, y + 1);
+                    }
                 }
             }
+            return;
         }
 
-        // Build maze from path: connect consecutive cells in path
-        for i in 0..(path.len() - 1) {
-            let (x1, y1) = path[i];
-            let (x2, y2) = path[i + 1];
-            maze.remove_wall(x1, y1, x2, y2);
+        if cycle_fits(width, height) {
+            carve_cycle(maze, width, height, complexity, rng);
+            if self.params.cycle != Some(true) {
+                // Open the loop beside the entrance, so the corridor runs from
+                // the entrance to the exit moved onto the cut
+                let start = maze.start();
+                let cell = maze.cell_id(start.x, start.y);
+                let cut = pick_cut(maze, cell, rng);
+                maze.unlink(cell, cut);
+                maze.set_goal(border_endpoint(maze, cut));
+            }
+            return;
         }
 
-        // Optionally break some connections based on complexity
-        // Higher complexity = break more connections (create more loops)
-        if complexity > 0.3 {
-            let break_probability = (complexity - 0.3) * 0.5; // Range: 0.0 to 0.35
-            for i in 0..(path.len() - 1) {
-                if rng.gen::<f64>() < break_probability {
-                    // Break this connection by adding a wall
-                    let (x1, y1) = path[i];
-                    let (x2, y2) = path[i + 1];
-                    // Get cells separately to avoid borrow checker issues
-                    {
-                        if let Some(mut_c1) = maze.get_cell_mut(x1, y1) {
-                            if x2 == x1 + 1 {
-                                mut_c1.east = true;
-                            } else if x2 + 1 == x1 {
-                                mut_c1.west = true;
-                            } else if y2 == y1 + 1 {
-                                mut_c1.south = true;
-                            } else if y2 + 1 == y1 {
-                                mut_c1.north = true;
-                            }
-                        }
-                    }
-                    {
-                        if let Some(mut_c2) = maze.get_cell_mut(x2, y2) {
-                            if x2 == x1 + 1 {
-                                mut_c2.west = true;
-                            } else if x2 + 1 == x1 {
-                                mut_c2.east = true;
-                            } else if y2 == y1 + 1 {
-                                mut_c2.north = true;
-                            } else if y2 + 1 == y1 {
-                                mut_c2.south = true;
-                            }
-                        }
-                    }
+        // Both sides odd, so there is no cycle: the corridor runs along the
+        // top or bottom row from a corner, then loops through the other rows.
+        // It starts at the entrance when that is a corner, otherwise at the
+        // exit, and otherwise at the top-left corner.
+        let corners = [(0, 0), (width - 1, 0), (0, height - 1), (width - 1, height - 1)];
+        let (start, goal) = (maze.start(), maze.goal());
+        let start_corner = corners.contains(&(start.x, start.y));
+        let from_goal = !start_corner && corners.contains(&(goal.x, goal.y));
+        let (cx, cy) = if from_goal {
+            (goal.x, goal.y)
+        } else if start_corner {
+            (start.x, start.y)
+        } else {
+            maze.set_start(Endpoint::new(0, 0, Some(Side::North)));
+            (0, 0)
+        };
+
+        // Loop through the other rows on their own, then copy the loop across
+        let mut rows = Maze::new(width, height - 1);
+        carve_cycle(&mut rows, width, height - 1, complexity, rng);
+        let offset = if cy == 0 { 1 } else { 0 };
+        for y in 0..height - 1 {
+            for x in 0..width {
+                let cell = rows.cell_id(x, y);
+                for next in rows.links(cell) {
+                    let (nx, ny) = rows.cell_coords(next);
+                    maze.remove_wall(x, y + offset, nx, ny + offset);
                 }
             }
         }
 
-        // Ensure connectivity from start to end (in case breaking connections disconnected them)
-        maze.ensure_connectivity();
-
-        maze
+        // Run the corner's row to the far corner, step into the loop and open
+        // it there; the other side of the cut is the far end of the corridor
+        for x in 0..width - 1 {
+            maze.remove_wall(x, cy, x + 1, cy);
+        }
+        let (x, y) = (width - 1 - cx, if cy == 0 { 1 } else { cy - 1 });
+        let cell = maze.cell_id(x, y);
+        let cut = pick_cut(maze, cell, rng);
+        maze.unlink(cell, cut);
+        maze.remove_wall(x, cy, x, y);
+        let end = border_endpoint(maze, cut);
+        if from_goal {
+            maze.set_start(end);
+        } else {
+            maze.set_goal(end);
+        }
     }
 }
 
+/// A loop neighbour of `cell` to open the loop towards, on the border if possible
+fn pick_cut(maze: &Maze, cell: CellId, rng: &mut ChaCha8Rng) -> CellId {
+    let (border, inner): (Vec<CellId>, Vec<CellId>) = maze
+        .links(cell)
+        .into_iter()
+        .partition(|&n| border_endpoint(maze, n).side.is_some());
+    let choices = if border.is_empty() { inner } else { border };
+    choices[rng.gen_range(0..choices.len())]
+}
+
+/// An endpoint at `cell`, opening a border wall of it if it has one
+fn border_endpoint(maze: &Maze, cell: CellId) -> Endpoint {
+    let (x, y) = maze.cell_coords(cell);
+    let side = [Side::South, Side::East, Side::North, Side::West]
+        .into_iter()
+        .find(|&side| maze.is_border(x, y, side));
+    Endpoint::new(x, y, side)
+}
+
+/// Whether a grid has a cycle through every cell
+fn cycle_fits(width: u32, height: u32) -> bool {
+    width >= 2 && height >= 2 && (width.is_multiple_of(2) || height.is_multiple_of(2))
+}
+
+/// Link a cycle through every cell in columns 0..width and rows 0..height
+/// Both sides are at least 2 and at least one is even.
+fn carve_cycle(maze: &mut Maze, width: u32, height: u32, complexity: f64, rng: &mut ChaCha8Rng) {
+    if !width.is_multiple_of(2) {
+        // Loop through the even columns, then detour through the last one;
+        // the right side of the right-hand blocks is never merged away
+        carve_cycle(maze, width - 1, height, complexity, rng);
+        let x = width - 1;
+        for y in (0..height).step_by(2) {
+            maze.add_wall(x - 1, y, x - 1, y + 1);
+            maze.remove_wall(x - 1, y, x, y);
+            maze.remove_wall(x, y, x, y + 1);
+            maze.remove_wall(x, y + 1, x - 1, y + 1);
+        }
+        return;
+    }
+    if !height.is_multiple_of(2) {
+        // The same for an odd row, below the bottom blocks
+        carve_cycle(maze, width, height - 1, complexity, rng);
+        let y = height - 1;
+        for x in (0..width).step_by(2) {
+            maze.add_wall(x, y - 1, x + 1, y - 1);
+            maze.remove_wall(x, y - 1, x, y);
+            maze.remove_wall(x, y, x + 1, y);
+            maze.remove_wall(x + 1, y, x + 1, y - 1);
+        }
+        return;
+    }
+
+    // A spanning tree over the 2x2 blocks decides which loops merge
+    let mut blocks = Maze::new(width / 2, height / 2);
+    RecursiveBacktracking.carve(&mut blocks, complexity, rng);
+
+    // Each block starts as a loop around its four cells
+    for y in (0..height).step_by(2) {
+        for x in (0..width).step_by(2) {
+            maze.remove_wall(x, y, x + 1, y);
+            maze.remove_wall(x + 1, y, x + 1, y + 1);
+            maze.remove_wall(x + 1, y + 1, x, y + 1);
+            maze.remove_wall(x, y + 1, x, y);
+        }
+    }
+
+    // Merge the loops of neighbouring blocks joined in the tree: drop the two
+    // facing sides and bridge across them
+    for by in 0..height / 2 {
+        for bx in 0..width / 2 {
+            let block = blocks.cell_id(bx, by);
+            let (x, y) = (bx * 2, by * 2);
+            if bx + 1 < width / 2 && blocks.is_linked(block, blocks.cell_id(bx + 1, by)) {
+                maze.add_wall(x + 1, y, x + 1, y + 1);
+                maze.add_wall(x + 2, y, x + 2, y + 1);
+                maze.remove_wall(x + 1, y, x + 2, y);
+                maze.remove_wall(x + 1, y + 1, x + 2, y + 1);
+            }
+            if by + 1 < height / 2 && blocks.is_linked(block, blocks.cell_id(bx, by + 1)) {
+                maze.add_wall(x, y + 1, x + 1, y + 1);
+                maze.add_wall(x, y + 2, x + 1, y + 2);
+                maze.remove_wall(x, y + 1, x, y + 2);
+                maze.remove_wall(x + 1, y + 1, x + 1, y + 2);
+            }
+        }
+    }
+}
@@ -1,109 +1,144 @@
-use crate::algorithms::MazeGenerator;
+use crate::algorithms::{pick_weighted, GenerateError, GeneratorOption, MazeGenerator};
+use crate::grid::{CellId, Grid};
 use crate::maze::Maze;
 use rand::Rng;
-use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 use std::collections::HashSet;
 
 pub struct HuntAndKill;
 
 impl MazeGenerator for HuntAndKill {
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze {
-        let mut maze = Maze::new(width, height);
-        let mut rng = match seed {
-            Some(s) => ChaCha8Rng::seed_from_u64(s),
-            None => {
-                let seed = rand::thread_rng().gen();
-                ChaCha8Rng::seed_from_u64(seed)
-            }
-        };
-        let mut visited = HashSet::new();
-
-        // Start at a random cell
-        let mut current_x = rng.gen_range(0..width);
-        let mut current_y = rng.gen_range(0..height);
-        visited.insert((current_x, current_y));
-
-        loop {
-            // Random walk carving until stuck
-            let mut stuck = false;
-            while !stuck {
-                let neighbors = maze.get_neighbors(current_x, current_y);
-                let unvisited_neighbors: Vec<(u32, u32)> = neighbors
-                    .into_iter()
-                    .filter(|&(nx, ny)| !visited.contains(&(nx, ny)))
-                    .collect();
-
-                if unvisited_neighbors.is_empty() {
-                    stuck = true;
-                } else {
-                    // Choose neighbor based on complexity
-                    let next_idx = if unvisited_neighbors.len() == 1 {
+    fn name(&self) -> &'static str {
+        "hunt_and_kill"
+    }
+
+    fn description(&self) -> &'static str {
+        "Random walk that hunts for a new start next to the maze when stuck"
+    }
+
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[GeneratorOption {
+            name: "complexity",
+            description: "Randomness of the walk",
+        }]
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        carve_tree(maze, complexity, rng);
+    }
+
+    fn carve_grid(
+        &self,
+        grid: &mut dyn Grid,
+        complexity: f64,
+        rng: &mut ChaCha8Rng,
+    ) -> Result<(), GenerateError> {
+        carve_tree(grid, complexity, rng);
+        Ok(())
+    }
+
+    fn supports_mask(&self) -> bool {
+        true
+    }
+
+    fn supports_grids(&self) -> bool {
+        true
+    }
+
+    fn supports_weights(&self) -> bool {
+        true
+    }
+}
+
+fn carve_tree(grid: &mut dyn Grid, complexity: f64, rng: &mut ChaCha8Rng) {
+    let mut visited = HashSet::new();
+
+    // Start at a random cell
+    let mut current = grid.random_cell(rng);
+    visited.insert(current);
+    grid.visit(current);
+
+    loop {
+        // Random walk carving until stuck
+        let mut stuck = false;
+        while !stuck {
+            let unvisited_neighbors: Vec<CellId> = grid
+                .neighbors(current)
+                .into_iter()
+                .filter(|n| !visited.contains(n))
+                .collect();
+
+            if unvisited_neighbors.is_empty() {
+                stuck = true;
+            } else {
+                // Choose neighbor based on complexity
+                let weighted = pick_weighted(
+                    &*grid,
+                    unvisited_neighbors.iter().map(|&n| (current, n)),
+                    rng,
+                );
+                let next_idx = if let Some(idx) = weighted {
+                    // A weighted grid, e.g. a directional bias, decides instead
+                    idx
+                } else if unvisited_neighbors.len() == 1 {
+                    0
+                } else if complexity < 0.1 {
+                    // Very low complexity: prefer first neighbor
+                    if rng.gen::<f64>() < 0.8 {
                         0
-                    } else if complexity < 0.1 {
-                        // Very low complexity: prefer first neighbor
-                        if rng.gen::<f64>() < 0.8 {
-                            0
-                        } else {
-                            rng.gen_range(1..unvisited_neighbors.len())
-                        }
                     } else {
-                        // Higher complexity: fully random
-                        rng.gen_range(0..unvisited_neighbors.len())
-                    };
-                    let (nx, ny) = unvisited_neighbors[next_idx];
-
-                    // Remove wall and mark as visited
-                    maze.remove_wall(current_x, current_y, nx, ny);
-                    visited.insert((nx, ny));
-                    current_x = nx;
-                    current_y = ny;
-                }
+                        rng.gen_range(1..unvisited_neighbors.len())
+                    }
+                } else {
+                    // Higher complexity: fully random
+                    rng.gen_range(0..unvisited_neighbors.len())
+                };
+                let next = unvisited_neighbors[next_idx];
+
+                // Remove wall and mark as visited
+                grid.link(current, next);
+                visited.insert(next);
+                grid.visit(next);
+                current = next;
             }
+        }
 
-            // Hunt for an unvisited cell adjacent to visited cells
-            let mut found = false;
-            for y in 0..height {
-                for x in 0..width {
-                    if !visited.contains(&(x, y)) {
-                        // Check if this cell has a visited neighbor
-                        let neighbors = maze.get_neighbors(x, y);
-                        let visited_neighbors: Vec<(u32, u32)> = neighbors
-                            .into_iter()
-                            .filter(|&(nx, ny)| visited.contains(&(nx, ny)))
-                            .collect();
-
-                        if !visited_neighbors.is_empty() {
-                            // Choose a visited neighbor to connect to
-                            let target_idx = if visited_neighbors.len() == 1 {
-                                0
-                            } else {
-                                rng.gen_range(0..visited_neighbors.len())
-                            };
-                            let (tx, ty) = visited_neighbors[target_idx];
-
-                            // Remove wall and mark as visited
-                            maze.remove_wall(x, y, tx, ty);
-                            visited.insert((x, y));
-                            current_x = x;
-                            current_y = y;
-                            found = true;
-                            break;
-                        }
-                    }
-                }
-                if found {
-                    break;
-                }
+        // Hunt, in cell id order, for an unvisited cell adjacent to visited cells
+        let mut found = false;
+        for cell in 0..grid.cell_count() {
+            if !grid.is_active(cell) || visited.contains(&cell) {
+                continue;
             }
 
-            // If no unvisited cell found, we're done
-            if !found {
+            // Check if this cell has a visited neighbor
+            let visited_neighbors: Vec<CellId> = grid
+                .neighbors(cell)
+                .into_iter()
+                .filter(|n| visited.contains(n))
+                .collect();
+
+            if !visited_neighbors.is_empty() {
+                // Choose a visited neighbor to connect to
+                let target_idx = if visited_neighbors.len() == 1 {
+                    0
+                } else {
+                    rng.gen_range(0..visited_neighbors.len())
+                };
+                let target = visited_neighbors[target_idx];
+
+                // Remove wall and mark as visited
+                grid.link(cell, target);
+                visited.insert(cell);
+                grid.visit(cell);
+                current = cell;
+                found = true;
                 break;
             }
         }
 
-        maze
+        // If no unvisited cell found, we're done
+        if !found {
+            break;
+        }
     }
 }
-
@@ -1,7 +1,8 @@
-use crate::algorithms::MazeGenerator;
+use crate::algorithms::{GenerateError, GeneratorOption, MazeGenerator, Params};
+use crate::field::{Field, Weighted, DEFAULT_CONTRAST};
+use crate::grid::{ensure_connectivity, CellId, Grid};
 use crate::maze::Maze;
 use rand::Rng;
-use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 
 /// Union-Find data structure for tracking connected components
@@ -46,64 +47,263 @@ impl UnionFind {
     }
 }
 
-pub struct Kruskal;
+/// Parameters for Kruskal; unset ones follow `complexity`
+#[derive(Debug, Clone, Default)]
+pub struct KruskalParams {
+    /// Share of the edge list that is shuffled before joining
+    pub shuffle: Option<f64>,
+    /// Chance of joining two regions when their shared wall comes up
+    pub join: Option<f64>,
+    /// Field that orders the walls in place of `shuffle` and `join`
+    pub field: Option<Field>,
+    /// How closely the order follows the field
+    pub contrast: Option<f64>,
+}
+
+impl KruskalParams {
+    /// Override the parameters named in `params`
+    pub fn apply(&mut self, params: &Params) -> Result<(), String> {
+        params.check_keys("kruskal", &["shuffle", "join", "field", "contrast"])?;
+        if let Some(shuffle) = params.get_probability("shuffle")? {
+            self.shuffle = Some(shuffle);
+        }
+        if let Some(join) = params.get_probability("join")? {
+            self.join = Some(join);
+        }
+        if let Some(field) = params.get("field")? {
+            self.field = Some(field);
+        }
+        if let Some(contrast) = params.get::<f64>("contrast")? {
+            if !contrast.is_finite() {
+                return Err(format!("Parameter contrast must be a finite number, got: {}", contrast));
+            }
+            self.contrast = Some(contrast);
+        }
+        if self.field.is_some() && (self.shuffle.is_some() || self.join.is_some()) {
+            return Err("Parameter field orders the walls itself; drop shuffle and join".to_string());
+        }
+        Ok(())
+    }
+
+    /// The parameters that are set, as they would be written
+    pub fn to_params(&self) -> Params {
+        let mut params = Params::new();
+        if let Some(shuffle) = &self.shuffle {
+            params.set("shuffle", &shuffle.to_string());
+        }
+        if let Some(join) = &self.join {
+            params.set("join", &join.to_string());
+        }
+        if let Some(field) = &self.field {
+            params.set("field", &field.spec());
+        }
+        if let Some(contrast) = &self.contrast {
+            params.set("contrast", &contrast.to_string());
+        }
+        params
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Kruskal {
+    pub params: KruskalParams,
+}
 
 impl MazeGenerator for Kruskal {
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze {
-        let mut maze = Maze::new(width, height);
-        let mut rng = match seed {
-            Some(s) => ChaCha8Rng::seed_from_u64(s),
-            None => {
-                let seed = rand::thread_rng().gen();
-                ChaCha8Rng::seed_from_u64(seed)
+    fn name(&self) -> &'static str {
+        "kruskal"
+    }
+
+    fn description(&self) -> &'static str {
+        "Joins random walls between separate regions until one remains; many short dead ends"
+    }
+
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[
+            GeneratorOption {
+                name: "complexity",
+                description: "How much of the wall list is shuffled; above 0.1 some walls are skipped",
+            },
+            GeneratorOption {
+                name: "shuffle",
+                description: "Share of the wall list shuffled before joining (0.0 to 1.0); ignored under --bias",
+            },
+            GeneratorOption {
+                name: "join",
+                description: "Chance of opening a wall between separate regions (0.0 to 1.0); ignored under --bias",
+            },
+            GeneratorOption {
+                name: "field",
+                description: "Order walls by a field instead: noise[:scale], radial or image:<path>",
+            },
+            GeneratorOption {
+                name: "contrast",
+                description: "How closely the field is followed (default 100; negative inverts it)",
+            },
+        ]
+    }
+
+    fn with_params(&self, params: &Params) -> Result<Box<dyn MazeGenerator>, String> {
+        let mut generator = self.clone();
+        generator.params.apply(params)?;
+        Ok(Box::new(generator))
+    }
+
+    fn params(&self) -> Params {
+        self.params.to_params()
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        self.carve_tree(maze, complexity, rng);
+    }
+
+    fn carve_grid(
+        &self,
+        grid: &mut dyn Grid,
+        complexity: f64,
+        rng: &mut ChaCha8Rng,
+    ) -> Result<(), GenerateError> {
+        self.carve_tree(grid, complexity, rng);
+        Ok(())
+    }
+
+    fn supports_mask(&self) -> bool {
+        true
+    }
+
+    fn supports_grids(&self) -> bool {
+        true
+    }
+
+    fn supports_weights(&self) -> bool {
+        true
+    }
+
+    fn supports_density(&self) -> bool {
+        true
+    }
+}
+
+impl Kruskal {
+    fn carve_tree(&self, grid: &mut dyn Grid, complexity: f64, rng: &mut ChaCha8Rng) {
+        let (shuffle, join) = (self.params.shuffle, self.params.join);
+        match &self.params.field {
+            // The field's weights order the walls in place of the shuffle and join chance
+            Some(field) => {
+                let contrast = self.params.contrast.unwrap_or(DEFAULT_CONTRAST);
+                let mut weighted = Weighted::new(grid, field, rng.gen(), contrast);
+                carve_tree(&mut weighted, shuffle, join, complexity, rng);
             }
-        };
-        let mut uf = UnionFind::new((width * height) as usize);
-
-        // Create list of all edges (walls between adjacent cells)
-        let mut edges = Vec::new();
-        for y in 0..height {
-            for x in 0..width {
-                if x < width - 1 {
-                    edges.push((x, y, x + 1, y));
-                }
-                if y < height - 1 {
-                    edges.push((x, y, x, y + 1));
-                }
+            None => carve_tree(grid, shuffle, join, complexity, rng),
+        }
+    }
+}
+
+/// The join chance `complexity` picks
+/// None opens every wall between separate regions.
+fn join_chance(complexity: f64) -> Option<f64> {
+    (complexity >= 0.1).then_some(0.3 + complexity * 0.7)
+}
+
+/// Join regions wall by wall; an unset shuffle or join chance follows the
+/// complexity, which a density map sets for each wall
+fn carve_tree(
+    grid: &mut dyn Grid,
+    shuffle: Option<f64>,
+    join: Option<f64>,
+    complexity: f64,
+    rng: &mut ChaCha8Rng,
+) {
+    let mut uf = UnionFind::new(grid.cell_count());
+
+    // Create list of all edges (walls between adjacent cells), each once,
+    // with the complexity between its two cells
+    let mut edges = Vec::new();
+    for cell in grid.cells() {
+        for neighbor in grid.neighbors(cell) {
+            if neighbor > cell {
+                let local = match (grid.complexity(cell), grid.complexity(neighbor)) {
+                    (Some(a), Some(b)) => (a + b) / 2.0,
+                    _ => complexity,
+                };
+                edges.push((cell, neighbor, local));
             }
         }
+    }
+    let varies = edges
+        .first()
+        .is_some_and(|&(a, _, _)| grid.complexity(a).is_some());
 
-        // Shuffle edges based on complexity
-        // Higher complexity = more randomness in edge selection
-        // At complexity 0.0, process edges in deterministic order (left-to-right, top-to-bottom)
-        // At complexity 1.0, fully randomize edge order
-        if complexity > 0.0 {
-            // Shuffle proportionally to complexity
-            let shuffle_count = (edges.len() as f64 * complexity) as usize;
+    // A weighted grid, e.g. a directional bias, orders the edges instead of
+    // the shuffle and join chance: heavier edges tend to join regions first
+    let weights: Option<Vec<f64>> = edges.iter().map(|&(a, b, _)| grid.weight(a, b)).collect();
+    let weighted = weights.is_some();
+    if let Some(weights) = weights {
+        let mut keyed: Vec<(f64, (CellId, CellId, f64))> = edges
+            .into_iter()
+            .zip(weights)
+            .map(|(edge, weight)| {
+                // ln(u) / w sorts like u^(1/w), but keeps heavy edges apart
+                // where u^(1/w) would round them all to 1.0
+                let key = if weight > 0.0 {
+                    rng.gen::<f64>().ln() / weight
+                } else {
+                    f64::NEG_INFINITY
+                };
+                (key, edge)
+            })
+            .collect();
+        keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+        edges = keyed.into_iter().map(|(_, edge)| edge).collect();
+    } else if varies && shuffle.is_none() {
+        // Each edge moves to a random place with the chance of its own
+        // complexity, so low-complexity regions keep their regular order
+        let len = edges.len() as f64;
+        let mut keyed: Vec<(f64, (CellId, CellId, f64))> = edges
+            .into_iter()
+            .enumerate()
+            .map(|(i, edge)| {
+                let key = if rng.gen::<f64>() < edge.2 {
+                    rng.gen::<f64>() * len
+                } else {
+                    i as f64
+                };
+                (key, edge)
+            })
+            .collect();
+        keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+        edges = keyed.into_iter().map(|(_, edge)| edge).collect();
+    } else {
+        let shuffle = shuffle.unwrap_or(complexity);
+        if shuffle > 0.0 {
+            // Shuffle part of the edge list
+            // At 0.0, process edges in deterministic order (cell id order)
+            // At 1.0, fully randomize edge order
+            let shuffle_count = (edges.len() as f64 * shuffle) as usize;
             for i in 0..shuffle_count {
                 let j = rng.gen_range(0..edges.len());
                 edges.swap(i, j);
             }
         }
+    }
 
-        // Process edges
-        for (x1, y1, x2, y2) in edges {
-            let idx1 = (y1 * width + x1) as usize;
-            let idx2 = (y2 * width + x2) as usize;
-
-            // If cells are in different sets, remove wall and union them
-            if uf.find(idx1) != uf.find(idx2) {
-                // Apply complexity-based filtering: higher complexity allows MORE edges (creates loops)
-                // At complexity 0.0: only create minimum spanning tree (no loops)
-                // At complexity 1.0: allow many extra connections (many loops)
-                if complexity < 0.1 || rng.gen::<f64>() < (0.3 + complexity * 0.7) {
-                    maze.remove_wall(x1, y1, x2, y2);
-                    uf.union(idx1, idx2);
-                }
+    // Process edges
+    for (a, b, local) in edges {
+        // If cells are in different sets, remove wall and union them
+        if uf.find(a) != uf.find(b) {
+            // A skipped wall leaves the regions apart for a later edge to join
+            let join = if weighted {
+                None
+            } else {
+                join.or_else(|| join_chance(local))
+            };
+            if join.is_none_or(|chance| rng.gen::<f64>() < chance) {
+                grid.link(a, b);
+                uf.union(a, b);
             }
         }
-
-        maze
     }
-}
 
+    // Skipped edges can leave separate regions behind
+    ensure_connectivity(grid);
+}
@@ -1,7 +1,112 @@
+use crate::events::{GenerationEvent, Recorder};
+use crate::grid::{CellId, Grid};
 use crate::maze::Maze;
+use rand::Rng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// A setting a generator responds to, as listed by the registry
+#[derive(Debug, Clone, Copy)]
+pub struct GeneratorOption {
+    pub name: &'static str,
+    pub description: &'static str,
+}
 
 /// Trait for maze generation algorithms
 pub trait MazeGenerator {
+    /// Canonical name, as used in config files and on the command line
+    fn name(&self) -> &'static str;
+
+    /// Other names the generator can be selected by
+    fn aliases(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// One-line summary of the kind of maze the generator makes
+    fn description(&self) -> &'static str;
+
+    /// Settings the generator responds to and what each one controls
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[]
+    }
+
+    /// A copy of this generator with the given parameters applied
+    ///
+    /// Parameters override what `complexity` would otherwise pick; anything
+    /// not set keeps following it. Unknown keys and bad values are errors,
+    /// and the default accepts no parameters at all.
+    fn with_params(&self, params: &Params) -> Result<Box<dyn MazeGenerator>, String> {
+        params.check_keys(self.name(), &[])?;
+        Err(format!("Algorithm {} takes no parameters", self.name()))
+    }
+
+    /// The parameters this generator was configured with by `with_params`
+    fn params(&self) -> Params {
+        Params::new()
+    }
+
+    /// Carve passages into a maze that starts with every wall intact
+    ///
+    /// Callers check the maze with `check` first; `carve` assumes it passed.
+    ///
+    /// # Arguments
+    /// * `maze` - The maze to carve; its size, mask and endpoints are already set
+    /// * `complexity` - Complexity parameter (0.0 to 1.0), affects algorithm behavior
+    /// * `rng` - Source of every random choice, so a fixed seed reproduces the maze
+    ///
+    /// The carved maze is guaranteed to be solvable
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng);
+
+    /// Carve passages into any grid topology
+    ///
+    /// Algorithms that only need cells, neighbours and links implement this,
+    /// and run unchanged on every `Grid`. The default reports that the
+    /// algorithm depends on the square layout of `Maze`.
+    fn carve_grid(
+        &self,
+        _grid: &mut dyn Grid,
+        _complexity: f64,
+        _rng: &mut ChaCha8Rng,
+    ) -> Result<(), GenerateError> {
+        Err(GenerateError::UnsupportedTopology {
+            algorithm: self.name().to_string(),
+            topology: "non-square grids".to_string(),
+        })
+    }
+
+    /// Whether `carve` keeps to the active cells of a masked maze
+    fn supports_mask(&self) -> bool {
+        false
+    }
+
+    /// Whether `carve_grid` works on grids other than `Maze`
+    fn supports_grids(&self) -> bool {
+        false
+    }
+
+    /// Whether `carve_grid` follows `Grid::weight` when choosing passages,
+    /// which is how a directional bias reaches the generator
+    fn supports_weights(&self) -> bool {
+        false
+    }
+
+    /// Whether `carve_grid` reads `Grid::complexity` for each cell, which is
+    /// how a density map reaches the generator
+    fn supports_density(&self) -> bool {
+        false
+    }
+
+    /// Most cells `carve` can handle, for algorithms that run out of time or stack
+    fn max_cells(&self) -> Option<usize> {
+        None
+    }
+
+    /// Check that `carve` can handle a maze: it has cells, and any mask and
+    /// its size are within what the algorithm supports
+    fn check(&self, maze: &Maze) -> Result<(), GenerateError> {
+        check_maze(self, maze)
+    }
+
     /// Generate a maze with the given dimensions and complexity
     /// 
     /// # Arguments
@@ -11,8 +116,142 @@ pub trait MazeGenerator {
     /// * `seed` - Optional seed for reproducible generation. If None, uses random seed.
     /// 
     /// # Returns
-    /// A generated maze that is guaranteed to be solvable
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze;
+    /// A generated maze that is guaranteed to be solvable, with the seed that
+    /// reproduces it, or why the algorithm could not generate one of this size
+    fn generate(
+        &self,
+        width: u32,
+        height: u32,
+        complexity: f64,
+        seed: Option<u64>,
+    ) -> Result<GeneratedMaze, GenerateError> {
+        let mut maze = Maze::new(width, height);
+        self.check(&maze)?;
+        let seed = resolve_seed(seed);
+        self.carve(&mut maze, complexity, &mut seeded_rng(Some(seed)));
+        Ok(GeneratedMaze {
+            maze,
+            seed,
+            algorithm: self.name(),
+            params: self.params(),
+        })
+    }
+
+    /// Generate a maze as `generate` does, along with every step taken
+    ///
+    /// The same seed gives the same maze with or without events; frontends
+    /// can replay the events to animate generation.
+    fn generate_with_events(
+        &self,
+        width: u32,
+        height: u32,
+        complexity: f64,
+        seed: Option<u64>,
+    ) -> Result<(GeneratedMaze, Vec<GenerationEvent>), GenerateError> {
+        let mut maze = Maze::new(width, height);
+        self.check(&maze)?;
+        let seed = resolve_seed(seed);
+        maze.record_events();
+        self.carve(&mut maze, complexity, &mut seeded_rng(Some(seed)));
+        let events = maze.take_events();
+        let generated = GeneratedMaze {
+            maze,
+            seed,
+            algorithm: self.name(),
+            params: self.params(),
+        };
+        Ok((generated, events))
+    }
+
+    /// Carve any grid as `carve_grid` does, and return every step taken
+    fn carve_grid_with_events(
+        &self,
+        grid: &mut dyn Grid,
+        complexity: f64,
+        rng: &mut ChaCha8Rng,
+    ) -> Result<Vec<GenerationEvent>, GenerateError> {
+        let mut recorder = Recorder::new(grid);
+        self.carve_grid(&mut recorder, complexity, rng)?;
+        Ok(recorder.into_events())
+    }
+}
+
+/// The checks `MazeGenerator::check` makes by default, for generators that
+/// add checks of their own
+pub fn check_maze<G: MazeGenerator + ?Sized>(generator: &G, maze: &Maze) -> Result<(), GenerateError> {
+    let cells = maze.active_count();
+    if cells == 0 {
+        return Err(GenerateError::InvalidDimensions {
+            width: maze.width(),
+            height: maze.height(),
+        });
+    }
+    if maze.mask().is_some() && !generator.supports_mask() {
+        return Err(GenerateError::UnsupportedTopology {
+            algorithm: generator.name().to_string(),
+            topology: "masks".to_string(),
+        });
+    }
+    if let Some(max_cells) = generator.max_cells().filter(|&max| cells > max) {
+        return Err(GenerateError::ResourceLimit {
+            algorithm: generator.name().to_string(),
+            cells,
+            max_cells,
+        });
+    }
+    Ok(())
+}
+
+/// A generated maze along with everything needed to generate it again
+#[derive(Debug, Clone)]
+pub struct GeneratedMaze {
+    pub maze: Maze,
+    /// The seed that was used, drawn at random if none was given
+    pub seed: u64,
+    /// Canonical name of the generator
+    pub algorithm: &'static str,
+    /// Parameters the generator was configured with
+    pub params: Params,
+}
+
+/// The seed to generate with: the given one, or a random one if None
+pub fn resolve_seed(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(|| rand::thread_rng().gen())
+}
+
+/// Create the random number generator for a seed, drawing a random seed if None
+pub fn seeded_rng(seed: Option<u64>) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(resolve_seed(seed))
+}
+
+/// Pick one of a non-empty list of edges at random, in proportion to the grid's weights
+///
+/// Returns None when the grid has no weights, so the generator can fall back
+/// to its own choice and draw exactly what it drew before.
+pub fn pick_weighted<I>(grid: &dyn Grid, edges: I, rng: &mut ChaCha8Rng) -> Option<usize>
+where
+    I: IntoIterator<Item = (CellId, CellId)>,
+{
+    let weights: Vec<f64> = edges
+        .into_iter()
+        .map(|(a, b)| grid.weight(a, b))
+        .collect::<Option<_>>()?;
+    if weights.len() <= 1 {
+        return Some(0);
+    }
+
+    let total: f64 = weights.iter().sum();
+    if total <= 0.0 {
+        return Some(rng.gen_range(0..weights.len()));
+    }
+    let mut target = rng.gen::<f64>() * total;
+    for (i, &weight) in weights.iter().enumerate() {
+        if target < weight {
+            return Some(i);
+        }
+        target -= weight;
+    }
+    Some(weights.len() - 1)
 }
 
 pub mod recursive_backtracking;
@@ -34,7 +273,12 @@ pub mod drunkards_walk;
 pub mod random_obstacle;
 pub mod hamiltonian;
 pub mod voronoi;
-pub mod braid_postprocess;
+pub mod dungeon;
+pub mod origin_shift;
+pub mod composite;
+pub mod error;
+pub mod params;
+pub mod registry;
 
 pub use recursive_backtracking::RecursiveBacktracking;
 pub use kruskal::Kruskal;
@@ -55,4 +299,10 @@ pub use drunkards_walk::DrunkardsWalk;
 pub use random_obstacle::RandomObstacle;
 pub use hamiltonian::Hamiltonian;
 pub use voronoi::Voronoi;
+pub use dungeon::Dungeon;
+pub use origin_shift::OriginShift;
+pub use composite::Composite;
+pub use error::GenerateError;
+pub use params::Params;
+pub use registry::Registry;
 
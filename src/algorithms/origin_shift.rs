@@ -0,0 +1,246 @@
+use crate::algorithms::{GeneratorOption, MazeGenerator, Params};
+use crate::grid::{CellId, Grid};
+use crate::maze::Maze;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use std::collections::VecDeque;
+
+/// Parameters for origin shift
+#[derive(Debug, Clone, Default)]
+pub struct OriginShiftParams {
+    /// Number of times the origin is moved
+    pub shifts: Option<usize>,
+}
+
+impl OriginShiftParams {
+    /// Override the parameters named in `params`
+    pub fn apply(&mut self, params: &Params) -> Result<(), String> {
+        params.check_keys("origin_shift", &["shifts"])?;
+        if let Some(shifts) = params.get("shifts")? {
+            self.shifts = Some(shifts);
+        }
+        Ok(())
+    }
+
+    /// The parameters that are set, as they would be written
+    pub fn to_params(&self) -> Params {
+        let mut params = Params::new();
+        if let Some(shifts) = &self.shifts {
+            params.set("shifts", &shifts.to_string());
+        }
+        params
+    }
+}
+
+/// Keeps the maze as a tree rooted at an origin cell and moves the origin
+///
+/// Every cell but the origin points at a parent, and the passages are exactly
+/// those pointers. Moving the origin to a neighbour points the old origin at
+/// it and drops the neighbour's own pointer, so each shift opens at most one
+/// wall and closes one, and the maze stays perfect throughout.
+#[derive(Debug, Clone, Default)]
+pub struct OriginShift {
+    pub params: OriginShiftParams,
+}
+
+impl MazeGenerator for OriginShift {
+    fn name(&self) -> &'static str {
+        "origin_shift"
+    }
+
+    fn description(&self) -> &'static str {
+        "Starts from a regular tree and moves its root at random; can keep evolving a maze"
+    }
+
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[
+            GeneratorOption {
+                name: "complexity",
+                description: "Shifts per cell, from 0 (the regular starting tree) to 20",
+            },
+            GeneratorOption {
+                name: "shifts",
+                description: "Number of shifts in total, in place of complexity",
+            },
+        ]
+    }
+
+    fn with_params(&self, params: &Params) -> Result<Box<dyn MazeGenerator>, String> {
+        let mut generator = self.clone();
+        generator.params.apply(params)?;
+        Ok(Box::new(generator))
+    }
+
+    fn params(&self) -> Params {
+        self.params.to_params()
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        let shifts = self
+            .params
+            .shifts
+            .unwrap_or_else(|| (maze.active_count() as f64 * complexity * 20.0).round() as usize);
+
+        let mut tree = Tree::carve(maze);
+        for _ in 0..shifts {
+            tree.shift(maze, rng);
+        }
+    }
+
+    fn supports_mask(&self) -> bool {
+        true
+    }
+}
+
+/// Walls changed by one shift of the origin
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shift {
+    /// Cells joined by the passage that was opened
+    pub opened: (CellId, CellId),
+    /// Cells that the passage which was closed used to join
+    pub closed: (CellId, CellId),
+}
+
+/// A perfect maze that can keep changing shape
+///
+/// Each call to `shift` moves the origin of the maze's tree one step and
+/// rearranges at most one wall, so the maze can be shifted a little at a time
+/// while someone is walking through it. It is perfect after every shift.
+#[derive(Debug, Clone)]
+pub struct EvolvingMaze {
+    maze: Maze,
+    tree: Tree,
+}
+
+impl EvolvingMaze {
+    /// Take over a perfect maze, such as one made by any tree generator
+    /// Fails if the active cells are not joined by exactly one path each.
+    pub fn new(maze: Maze) -> Result<Self, String> {
+        let tree = Tree::of(&maze)?;
+        Ok(EvolvingMaze { maze, tree })
+    }
+
+    /// The maze as it stands
+    pub fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    /// Stop evolving and keep the maze
+    pub fn into_maze(self) -> Maze {
+        self.maze
+    }
+
+    /// The cell the tree is currently rooted at
+    pub fn origin(&self) -> CellId {
+        self.tree.origin
+    }
+
+    /// Move the origin to a random neighbour
+    /// Returns the walls that changed, or None if the origin moved along an
+    /// existing passage and the maze looks the same.
+    pub fn shift(&mut self, rng: &mut ChaCha8Rng) -> Option<Shift> {
+        self.tree.shift(&mut self.maze, rng)
+    }
+
+    /// Shift `count` times, returning every change in order
+    pub fn shift_n(&mut self, count: usize, rng: &mut ChaCha8Rng) -> Vec<Shift> {
+        (0..count).filter_map(|_| self.shift(rng)).collect()
+    }
+}
+
+/// Parent pointers of a spanning tree, rooted at the origin
+#[derive(Debug, Clone)]
+struct Tree {
+    parent: Vec<Option<CellId>>,
+    origin: CellId,
+}
+
+impl Tree {
+    /// Carve a regular starting tree into a grid with every wall intact
+    /// A breadth-first search from the last cell gives long straight
+    /// corridors, which the shifts then break up.
+    fn carve(grid: &mut dyn Grid) -> Self {
+        let origin = *grid.cells().last().expect("checked to have cells");
+        let tree = Tree::search(grid, origin, |grid, cell| grid.neighbors(cell));
+        for cell in grid.cells() {
+            if let Some(parent) = tree.parent[cell] {
+                grid.link(cell, parent);
+            }
+        }
+        tree
+    }
+
+    /// The tree of a grid's existing passages, if they form one
+    fn of(grid: &dyn Grid) -> Result<Self, String> {
+        let cells = grid.cells();
+        let Some(&origin) = cells.last() else {
+            return Err("Maze has no cells".to_string());
+        };
+        let tree = Tree::search(grid, origin, |grid, cell| grid.links(cell));
+
+        let reached = cells.iter().filter(|&&cell| tree.parent[cell].is_some()).count() + 1;
+        if reached < cells.len() {
+            return Err(format!(
+                "Maze is not connected: {} of {} cells are reachable",
+                reached,
+                cells.len()
+            ));
+        }
+        let passages: usize = cells.iter().map(|&cell| grid.links(cell).len()).sum::<usize>() / 2;
+        if passages != cells.len() - 1 {
+            return Err(format!(
+                "Maze is not perfect: it has {} loops",
+                passages - (cells.len() - 1)
+            ));
+        }
+        Ok(tree)
+    }
+
+    /// Breadth-first tree from `origin` over the cells `next` leads to
+    fn search(
+        grid: &dyn Grid,
+        origin: CellId,
+        next: impl Fn(&dyn Grid, CellId) -> Vec<CellId>,
+    ) -> Self {
+        let mut parent = vec![None; grid.cell_count()];
+        let mut seen = vec![false; grid.cell_count()];
+        let mut queue = VecDeque::from([origin]);
+        seen[origin] = true;
+        while let Some(cell) = queue.pop_front() {
+            for neighbor in next(grid, cell) {
+                if !seen[neighbor] {
+                    seen[neighbor] = true;
+                    parent[neighbor] = Some(cell);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        Tree { parent, origin }
+    }
+
+    /// Move the origin to a random neighbour, rewiring the passages to match
+    fn shift(&mut self, grid: &mut dyn Grid, rng: &mut ChaCha8Rng) -> Option<Shift> {
+        let neighbors = grid.neighbors(self.origin);
+        if neighbors.is_empty() {
+            return None;
+        }
+        let next = neighbors[rng.gen_range(0..neighbors.len())];
+        let old_parent = self.parent[next].expect("only the origin has no parent");
+
+        let origin = self.origin;
+        self.parent[origin] = Some(next);
+        self.parent[next] = None;
+        self.origin = next;
+
+        if old_parent == origin {
+            // The passage is already open; only its direction in the tree turns
+            return None;
+        }
+        grid.unlink(next, old_parent);
+        grid.link(origin, next);
+        Some(Shift {
+            opened: (origin, next),
+            closed: (next, old_parent),
+        })
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Algorithm parameters as written in config or on the command line
+///
+/// Values are kept as text until a generator parses them into its own
+/// typed parameter struct, so each generator decides which keys it accepts.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Params {
+    values: BTreeMap<String, String>,
+}
+
+impl Params {
+    /// Create an empty parameter set
+    pub fn new() -> Self {
+        Params::default()
+    }
+
+    /// Parse a "key=value" assignment
+    pub fn parse_assignment(s: &str) -> Result<(String, String), String> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("Parameter must be \"key=value\", got: {}", s))?;
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(format!("Parameter has no name: {}", s));
+        }
+        Ok((key.to_lowercase(), value.trim().to_string()))
+    }
+
+    /// Set a parameter, replacing any earlier value
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.values.insert(key.to_lowercase(), value.to_string());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Every parameter, ordered by name
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Fail on the first parameter a generator does not know
+    pub fn check_keys(&self, generator: &str, known: &[&str]) -> Result<(), String> {
+        match self.values.keys().find(|key| !known.contains(&key.as_str())) {
+            Some(key) => Err(format!(
+                "Algorithm {} has no parameter {} (known: {})",
+                generator,
+                key,
+                if known.is_empty() { "none".to_string() } else { known.join(", ") }
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Parse a parameter, if it is set
+    pub fn get<T>(&self, key: &str) -> Result<Option<T>, String>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.values
+            .get(key)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|e| format!("Invalid value for parameter {}: {} ({})", key, value, e))
+            })
+            .transpose()
+    }
+
+    /// Parse a probability parameter, which must lie between 0.0 and 1.0
+    pub fn get_probability(&self, key: &str) -> Result<Option<f64>, String> {
+        match self.get::<f64>(key)? {
+            Some(p) if !(0.0..=1.0).contains(&p) => {
+                Err(format!("Parameter {} must be between 0.0 and 1.0, got: {}", key, p))
+            }
+            p => Ok(p),
+        }
+    }
+}
+
+impl fmt::Display for Params {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pairs: Vec<String> = self.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        write!(f, "{}", pairs.join(", "))
+    }
+}
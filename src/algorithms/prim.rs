@@ -1,79 +1,347 @@
-use crate::algorithms::MazeGenerator;
+use crate::algorithms::{GenerateError, GeneratorOption, MazeGenerator, Params};
+use crate::field::{Field, Weighted, DEFAULT_CONTRAST};
+use crate::grid::{CellId, Grid};
 use crate::maze::Maze;
 use rand::Rng;
-use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
-use std::collections::HashSet;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
 
-pub struct Prim;
+/// Parameters for Prim; unset ones follow `complexity`
+#[derive(Debug, Clone, Default)]
+pub struct PrimParams {
+    /// Field that picks the frontier wall in place of `complexity`
+    pub field: Option<Field>,
+    /// How closely the picks follow the field
+    pub contrast: Option<f64>,
+}
+
+impl PrimParams {
+    /// Override the parameters named in `params`
+    pub fn apply(&mut self, params: &Params) -> Result<(), String> {
+        params.check_keys("prim", &["field", "contrast"])?;
+        if let Some(field) = params.get("field")? {
+            self.field = Some(field);
+        }
+        if let Some(contrast) = params.get::<f64>("contrast")? {
+            if !contrast.is_finite() {
+                return Err(format!("Parameter contrast must be a finite number, got: {}", contrast));
+            }
+            self.contrast = Some(contrast);
+        }
+        Ok(())
+    }
+
+    /// The parameters that are set, as they would be written
+    pub fn to_params(&self) -> Params {
+        let mut params = Params::new();
+        if let Some(field) = &self.field {
+            params.set("field", &field.spec());
+        }
+        if let Some(contrast) = &self.contrast {
+            params.set("contrast", &contrast.to_string());
+        }
+        params
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Prim {
+    pub params: PrimParams,
+}
 
 impl MazeGenerator for Prim {
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze {
-        let mut maze = Maze::new(width, height);
-        let mut rng = match seed {
-            Some(s) => ChaCha8Rng::seed_from_u64(s),
-            None => {
-                let seed = rand::thread_rng().gen();
-                ChaCha8Rng::seed_from_u64(seed)
+    fn name(&self) -> &'static str {
+        "prim"
+    }
+
+    fn description(&self) -> &'static str {
+        "Grows outward from a frontier of walls; short branches radiating from the start"
+    }
+
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[
+            GeneratorOption {
+                name: "complexity",
+                description: "Randomness of the frontier wall picked next",
+            },
+            GeneratorOption {
+                name: "field",
+                description: "Pick frontier walls by a field instead: noise[:scale], radial or image:<path>",
+            },
+            GeneratorOption {
+                name: "contrast",
+                description: "How closely the field is followed (default 100; negative inverts it)",
+            },
+        ]
+    }
+
+    fn with_params(&self, params: &Params) -> Result<Box<dyn MazeGenerator>, String> {
+        let mut generator = self.clone();
+        generator.params.apply(params)?;
+        Ok(Box::new(generator))
+    }
+
+    fn params(&self) -> Params {
+        self.params.to_params()
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        self.carve_tree(maze, complexity, rng);
+    }
+
+    fn carve_grid(
+        &self,
+        grid: &mut dyn Grid,
+        complexity: f64,
+        rng: &mut ChaCha8Rng,
+    ) -> Result<(), GenerateError> {
+        self.carve_tree(grid, complexity, rng);
+        Ok(())
+    }
+
+    fn supports_mask(&self) -> bool {
+        true
+    }
+
+    fn supports_grids(&self) -> bool {
+        true
+    }
+
+    fn supports_weights(&self) -> bool {
+        true
+    }
+
+    fn supports_density(&self) -> bool {
+        true
+    }
+}
+
+impl Prim {
+    fn carve_tree(&self, grid: &mut dyn Grid, complexity: f64, rng: &mut ChaCha8Rng) {
+        match &self.params.field {
+            // The field's weights pick the frontier wall in place of complexity
+            Some(field) => {
+                let contrast = self.params.contrast.unwrap_or(DEFAULT_CONTRAST);
+                let mut weighted = Weighted::new(grid, field, rng.gen(), contrast);
+                carve_tree(&mut weighted, complexity, rng);
             }
-        };
-        let mut in_tree = HashSet::new();
-        let mut frontier = Vec::new();
-
-        // Start at a random cell
-        let start_x = rng.gen_range(0..width);
-        let start_y = rng.gen_range(0..height);
-        in_tree.insert((start_x, start_y));
-        
-        // Add neighbors to frontier
-        for neighbor in maze.get_neighbors(start_x, start_y) {
-            frontier.push((start_x, start_y, neighbor.0, neighbor.1));
+            None => carve_tree(grid, complexity, rng),
         }
+    }
+}
 
-        while !frontier.is_empty() {
-            // Select an edge from frontier based on complexity
-            // Lower complexity = prefer earlier edges (more deterministic growth)
-            // Higher complexity = more random selection
-            let edge_idx = if frontier.len() == 1 {
-                0
-            } else if complexity < 0.1 {
-                // Very low complexity: always choose first edge (deterministic tree growth)
-                0
-            } else {
-                // Higher complexity: bias toward random selection
-                let bias = (1.0 - complexity).max(0.0);
-                if rng.gen::<f64>() < bias {
-                    // Bias toward first edge when complexity is low
-                    if rng.gen::<f64>() < 0.7 {
-                        0
-                    } else {
-                        rng.gen_range(1..frontier.len())
-                    }
+fn carve_tree(grid: &mut dyn Grid, complexity: f64, rng: &mut ChaCha8Rng) {
+    let mut in_tree = HashSet::new();
+    let mut frontier = Vec::new();
+
+    // Start at a random cell
+    let start = grid.random_cell(rng);
+
+    // A weighted grid, e.g. a directional bias, decides instead
+    if grid
+        .neighbors(start)
+        .first()
+        .is_some_and(|&n| grid.weight(start, n).is_some())
+    {
+        carve_weighted(grid, start, rng);
+        return;
+    }
+    // So does a density map, with a complexity for each cell
+    if grid.complexity(start).is_some() {
+        carve_local(grid, start, rng);
+        return;
+    }
+
+    in_tree.insert(start);
+    grid.visit(start);
+
+    // Add neighbors to frontier
+    for neighbor in grid.neighbors(start) {
+        frontier.push((start, neighbor));
+    }
+
+    while !frontier.is_empty() {
+        // Select an edge from frontier based on complexity
+        // Lower complexity = prefer earlier edges (more deterministic growth)
+        // Higher complexity = more random selection
+        let edge_idx = if frontier.len() == 1 {
+            0
+        } else if complexity < 0.1 {
+            // Very low complexity: always choose first edge (deterministic tree growth)
+            0
+        } else {
+            // Higher complexity: bias toward random selection
+            let bias = (1.0 - complexity).max(0.0);
+            if rng.gen::<f64>() < bias {
+                // Bias toward first edge when complexity is low
+                if rng.gen::<f64>() < 0.7 {
+                    0
                 } else {
-                    rng.gen_range(0..frontier.len())
+                    rng.gen_range(1..frontier.len())
                 }
-            };
-
-            let (x1, y1, x2, y2) = frontier.remove(edge_idx);
-
-            // If the destination cell is not in the tree, add it
-            if !in_tree.contains(&(x2, y2)) {
-                // Remove wall between cells
-                maze.remove_wall(x1, y1, x2, y2);
-                
-                // Add destination to tree
-                in_tree.insert((x2, y2));
-
-                // Add new edges to frontier
-                for neighbor in maze.get_neighbors(x2, y2) {
-                    if !in_tree.contains(&neighbor) {
-                        frontier.push((x2, y2, neighbor.0, neighbor.1));
-                    }
+            } else {
+                rng.gen_range(0..frontier.len())
+            }
+        };
+
+        let (from, to) = frontier.remove(edge_idx);
+
+        // If the destination cell is not in the tree, add it
+        if !in_tree.contains(&to) {
+            // Remove wall between cells
+            grid.link(from, to);
+
+            // Add destination to tree
+            in_tree.insert(to);
+            grid.visit(to);
+
+            // Add new edges to frontier
+            for neighbor in grid.neighbors(to) {
+                if !in_tree.contains(&neighbor) {
+                    frontier.push((to, neighbor));
                 }
             }
         }
+    }
+}
+
+/// A frontier wall in the race of `carve_weighted` and `carve_local`
+struct Candidate {
+    /// Walls of weight 0 wait until nothing else is left
+    stalled: bool,
+    /// When the wall comes up; the earliest is opened first
+    time: f64,
+    from: CellId,
+    to: CellId,
+}
+
+impl Ord for Candidate {
+    /// Reversed, so the max-heap pops the earliest wall
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .stalled
+            .cmp(&self.stalled)
+            .then(other.time.total_cmp(&self.time))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
 
-        maze
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
     }
 }
 
+impl Eq for Candidate {}
+
+/// Prim's algorithm on a weighted grid
+///
+/// Each frontier wall comes up after an exponential wait with its weight as
+/// the rate, counted from when it joined the frontier. Waits are memoryless,
+/// so the first wall to come up is any given one with a chance in proportion
+/// to its weight, as a weighted pick from the whole frontier would be, and a
+/// heap finds it without rescanning the frontier every step.
+fn carve_weighted(grid: &mut dyn Grid, start: CellId, rng: &mut ChaCha8Rng) {
+    let mut in_tree = vec![false; grid.cell_count()];
+    let mut frontier = BinaryHeap::new();
+    let mut clock = 0.0;
+
+    in_tree[start] = true;
+    grid.visit(start);
+    push_walls(grid, &in_tree, &mut frontier, start, clock, rng);
+
+    while let Some(candidate) = frontier.pop() {
+        if in_tree[candidate.to] {
+            continue;
+        }
+        if !candidate.stalled {
+            clock = candidate.time;
+        }
+        grid.link(candidate.from, candidate.to);
+        in_tree[candidate.to] = true;
+        grid.visit(candidate.to);
+        push_walls(grid, &in_tree, &mut frontier, candidate.to, clock, rng);
+    }
+}
+
+/// Add the walls from `cell` to cells outside the tree to the race, starting at `clock`
+fn push_walls(
+    grid: &dyn Grid,
+    in_tree: &[bool],
+    frontier: &mut BinaryHeap<Candidate>,
+    cell: CellId,
+    clock: f64,
+    rng: &mut ChaCha8Rng,
+) {
+    for neighbor in grid.neighbors(cell) {
+        if in_tree[neighbor] {
+            continue;
+        }
+        let weight = grid.weight(cell, neighbor).unwrap_or(1.0);
+        let wait = -(1.0 - rng.gen::<f64>()).ln() / weight;
+        frontier.push(Candidate {
+            stalled: weight <= 0.0,
+            time: if weight > 0.0 { clock + wait } else { 0.0 },
+            from: cell,
+            to: neighbor,
+        });
+    }
+}
+
+/// Prim's algorithm with a complexity for each cell, from a density map
+///
+/// Each frontier wall is keyed by when it was found, blended towards a random
+/// time by the complexity of the cell it leads to, and the lowest key is
+/// opened first. Simple regions grow outwards in order, as Prim's does at low
+/// complexity, and complex regions in random order.
+fn carve_local(grid: &mut dyn Grid, start: CellId, rng: &mut ChaCha8Rng) {
+    let mut in_tree = vec![false; grid.cell_count()];
+    let mut frontier = BinaryHeap::new();
+    let mut found = 0;
+
+    in_tree[start] = true;
+    grid.visit(start);
+    push_local_walls(grid, &in_tree, &mut frontier, start, &mut found, rng);
+
+    while let Some(candidate) = frontier.pop() {
+        if in_tree[candidate.to] {
+            continue;
+        }
+        grid.link(candidate.from, candidate.to);
+        in_tree[candidate.to] = true;
+        grid.visit(candidate.to);
+        push_local_walls(grid, &in_tree, &mut frontier, candidate.to, &mut found, rng);
+    }
+}
+
+/// Add the walls from `cell` to cells outside the tree, counting them in `found`
+fn push_local_walls(
+    grid: &dyn Grid,
+    in_tree: &[bool],
+    frontier: &mut BinaryHeap<Candidate>,
+    cell: CellId,
+    found: &mut usize,
+    rng: &mut ChaCha8Rng,
+) {
+    // No more walls than this are found, so random times span the whole run
+    let span = grid.cell_count() as f64 * 6.0;
+    for neighbor in grid.neighbors(cell) {
+        if in_tree[neighbor] {
+            continue;
+        }
+        let local = grid.complexity(neighbor).unwrap_or(1.0);
+        frontier.push(Candidate {
+            stalled: false,
+            time: (1.0 - local) * *found as f64 + local * rng.gen::<f64>() * span,
+            from: cell,
+            to: neighbor,
+        });
+        *found += 1;
+    }
+}
@@ -1,7 +1,6 @@
-use crate::algorithms::MazeGenerator;
+use crate::algorithms::{GeneratorOption, MazeGenerator};
 use crate::maze::Maze;
 use rand::Rng;
-use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 
 /// Union-Find data structure for tracking connected components
@@ -49,15 +48,24 @@ impl UnionFind {
 pub struct RandomObstacle;
 
 impl MazeGenerator for RandomObstacle {
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze {
-        let mut maze = Maze::new(width, height);
-        let mut rng = match seed {
-            Some(s) => ChaCha8Rng::seed_from_u64(s),
-            None => {
-                let seed = rand::thread_rng().gen();
-                ChaCha8Rng::seed_from_u64(seed)
-            }
-        };
+    fn name(&self) -> &'static str {
+        "random_obstacle"
+    }
+
+    fn description(&self) -> &'static str {
+        "Open grid with randomly placed walls, kept connected"
+    }
+
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[GeneratorOption {
+            name: "complexity",
+            description: "Density of obstacle walls",
+        }]
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        let width = maze.width();
+        let height = maze.height();
 
         // Start with all walls removed (open space)
         for y in 0..height {
@@ -99,32 +107,8 @@ impl MazeGenerator for RandomObstacle {
         }
 
         // Add back selected walls
-        for i in 0..num_obstacles.min(walls.len() as u32) as usize {
-            let (x1, y1, x2, y2) = walls[i];
-            // Add wall back by setting cell walls directly
-            // Get cells separately to avoid borrow checker issues
-            {
-                if let Some(mut_c1) = maze.get_cell_mut(x1, y1) {
-                    if x2 == x1 + 1 {
-                        // Vertical wall (east-west)
-                        mut_c1.east = true;
-                    } else if y2 == y1 + 1 {
-                        // Horizontal wall (north-south)
-                        mut_c1.south = true;
-                    }
-                }
-            }
-            {
-                if let Some(mut_c2) = maze.get_cell_mut(x2, y2) {
-                    if x2 == x1 + 1 {
-                        // Vertical wall (east-west)
-                        mut_c2.west = true;
-                    } else if y2 == y1 + 1 {
-                        // Horizontal wall (north-south)
-                        mut_c2.north = true;
-                    }
-                }
-            }
+        for &(x1, y1, x2, y2) in walls.iter().take(num_obstacles as usize) {
+            maze.add_wall(x1, y1, x2, y2);
         }
 
         // Ensure connectivity using union-find
@@ -142,13 +126,13 @@ impl MazeGenerator for RandomObstacle {
         }
 
         // Find all components
-        let mut components: std::collections::HashMap<usize, Vec<(u32, u32)>> =
-            std::collections::HashMap::new();
+        let mut components: std::collections::BTreeMap<usize, Vec<(u32, u32)>> =
+            std::collections::BTreeMap::new();
         for y in 0..height {
             for x in 0..width {
                 let idx = (y * width + x) as usize;
                 let root = uf.find(idx);
-                components.entry(root).or_insert_with(Vec::new).push((x, y));
+                components.entry(root).or_default().push((x, y));
             }
         }
 
@@ -192,8 +176,5 @@ impl MazeGenerator for RandomObstacle {
 
         // Ensure connectivity from start to end
         maze.ensure_connectivity();
-
-        maze
     }
 }
-
@@ -1,18 +1,35 @@
-use crate::algorithms::MazeGenerator;
+use crate::algorithms::{GeneratorOption, MazeGenerator};
 use crate::maze::Maze;
+use crate::postprocess::{Braid, PostProcessor};
+use rand_chacha::ChaCha8Rng;
 
 pub struct RecursiveBacktrackingBraided;
 
 impl MazeGenerator for RecursiveBacktrackingBraided {
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze {
+    fn name(&self) -> &'static str {
+        "recursive_backtracking_braided"
+    }
+
+    fn description(&self) -> &'static str {
+        "Recursive backtracking with dead ends removed afterwards"
+    }
+
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[GeneratorOption {
+            name: "complexity",
+            description: "Randomness of the carve and share of dead ends removed",
+        }]
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
         // First generate using recursive backtracking
         let generator = crate::algorithms::RecursiveBacktracking;
-        let mut maze = generator.generate(width, height, complexity, seed);
+        generator.carve(maze, complexity, rng);
 
         // Then apply braiding (remove dead ends)
-        crate::algorithms::braid_postprocess::braid_maze(&mut maze, complexity);
-
-        maze
+        Braid {
+            fraction: complexity,
+        }
+        .apply(maze, &[], rng);
     }
 }
-
@@ -1,21 +1,198 @@
-use crate::algorithms::MazeGenerator;
+use crate::algorithms::{GeneratorOption, MazeGenerator, Params};
 use crate::maze::Maze;
 use rand::Rng;
-use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
+use std::fmt;
+use std::str::FromStr;
 
-pub struct RecursiveDivision;
+/// Which way a chamber is split
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    /// Across the longer side, so chambers stay close to square
+    Shape,
+    /// A coin flip for every chamber
+    Random,
+    /// Always a horizontal wall, giving long east-west corridors
+    Horizontal,
+    /// Always a vertical wall, giving long north-south corridors
+    Vertical,
+}
+
+impl Orientation {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "shape" => Some(Orientation::Shape),
+            "random" => Some(Orientation::Random),
+            "horizontal" | "h" => Some(Orientation::Horizontal),
+            "vertical" | "v" => Some(Orientation::Vertical),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Orientation::Shape => "shape",
+            Orientation::Random => "random",
+            Orientation::Horizontal => "horizontal",
+            Orientation::Vertical => "vertical",
+        }
+    }
+}
+
+impl FromStr for Orientation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Orientation::parse(s).ok_or_else(|| {
+            format!("Unknown orientation: {} (use shape, random, horizontal or vertical)", s.trim())
+        })
+    }
+}
+
+impl fmt::Display for Orientation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Parameters for recursive division; unset ones keep the classic layout
+#[derive(Debug, Clone, Default)]
+pub struct RecursiveDivisionParams {
+    /// Chambers no larger than this on either side are left open as rooms
+    pub room_size: Option<u32>,
+    /// Cells opened by each door
+    pub door_width: Option<u32>,
+    /// Doors in each dividing wall
+    pub doors: Option<u32>,
+    /// How the direction of each wall is chosen
+    pub orientation: Option<Orientation>,
+}
+
+impl RecursiveDivisionParams {
+    /// Override the parameters named in `params`
+    pub fn apply(&mut self, params: &Params) -> Result<(), String> {
+        params.check_keys(
+            "recursive_division",
+            &["room_size", "door_width", "doors", "orientation"],
+        )?;
+        if let Some(room_size) = params.get("room_size")? {
+            self.room_size = Some(room_size);
+        }
+        if let Some(door_width) = params.get("door_width")? {
+            self.door_width = Some(door_width);
+        }
+        if let Some(doors) = params.get("doors")? {
+            self.doors = Some(doors);
+        }
+        if let Some(orientation) = params.get("orientation")? {
+            self.orientation = Some(orientation);
+        }
+        Ok(())
+    }
+
+    /// The parameters that are set, as they would be written
+    pub fn to_params(&self) -> Params {
+        let mut params = Params::new();
+        if let Some(room_size) = &self.room_size {
+            params.set("room_size", &room_size.to_string());
+        }
+        if let Some(door_width) = &self.door_width {
+            params.set("door_width", &door_width.to_string());
+        }
+        if let Some(doors) = &self.doors {
+            params.set("doors", &doors.to_string());
+        }
+        if let Some(orientation) = &self.orientation {
+            params.set("orientation", orientation.name());
+        }
+        params
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RecursiveDivision {
+    pub params: RecursiveDivisionParams,
+}
+
+/// The parameters with their defaults filled in
+struct Division {
+    room_size: u32,
+    door_width: u32,
+    doors: u32,
+    orientation: Orientation,
+    complexity: f64,
+}
+
+impl Division {
+    /// Cells of a wall running from `start` to `end` that are left open
+    /// The wall is cut into one stretch per door and each door placed within
+    /// its own stretch, so doors never overlap and spread along the wall.
+    fn door_cells(&self, start: u32, end: u32, rng: &mut ChaCha8Rng) -> Vec<u32> {
+        let length = end - start;
+        let width = self.door_width.min(length);
+        let doors = self.doors.min(length / width);
+        let mut open = Vec::new();
+        for i in 0..doors {
+            let from = start + i * length / doors;
+            let to = start + (i + 1) * length / doors;
+            let door = if self.complexity < 0.1 {
+                from + (to - from - width).div_ceil(2) // Deterministic: middle of the stretch
+            } else {
+                rng.gen_range(from..to - width + 1)
+            };
+            open.extend(door..door + width);
+        }
+        open
+    }
+}
 
 impl MazeGenerator for RecursiveDivision {
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze {
-        let mut maze = Maze::new(width, height);
-        let mut rng = match seed {
-            Some(s) => ChaCha8Rng::seed_from_u64(s),
-            None => {
-                let seed = rand::thread_rng().gen();
-                ChaCha8Rng::seed_from_u64(seed)
-            }
-        };
+    fn name(&self) -> &'static str {
+        "recursive_division"
+    }
+
+    fn description(&self) -> &'static str {
+        "Splits open space with walls that each keep one gap; long straight walls"
+    }
+
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[
+            GeneratorOption {
+                name: "complexity",
+                description: "How far from the edge of a chamber walls and gaps may be placed",
+            },
+            GeneratorOption {
+                name: "room_size",
+                description: "Leave chambers no larger than this on either side open as rooms (default 1)",
+            },
+            GeneratorOption {
+                name: "door_width",
+                description: "Cells opened by each door (default 1)",
+            },
+            GeneratorOption {
+                name: "doors",
+                description: "Doors in each dividing wall, as far as the wall has room (default 1)",
+            },
+            GeneratorOption {
+                name: "orientation",
+                description: "Wall direction: shape (across the longer side), random, horizontal, vertical",
+            },
+        ]
+    }
+
+    fn with_params(&self, params: &Params) -> Result<Box<dyn MazeGenerator>, String> {
+        let mut generator = self.clone();
+        generator.params.apply(params)?;
+        Ok(Box::new(generator))
+    }
+
+    fn params(&self) -> Params {
+        self.params.to_params()
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        let width = maze.width();
+        let height = maze.height();
 
         // Start with all walls removed (open space)
         // Remove all walls initially
@@ -30,6 +207,14 @@ impl MazeGenerator for RecursiveDivision {
             }
         }
 
+        let division = Division {
+            room_size: self.params.room_size.unwrap_or(1).max(1),
+            door_width: self.params.door_width.unwrap_or(1).max(1),
+            doors: self.params.doors.unwrap_or(1).max(1),
+            orientation: self.params.orientation.unwrap_or(Orientation::Shape),
+            complexity,
+        };
+
         // Recursively divide the space
         fn divide(
             maze: &mut Maze,
@@ -38,27 +223,38 @@ impl MazeGenerator for RecursiveDivision {
             y1: u32,
             x2: u32,
             y2: u32,
-            complexity: f64,
+            division: &Division,
         ) {
             let width = x2 - x1;
             let height = y2 - y1;
+            let complexity = division.complexity;
 
-            // Base case: region too small to divide
+            // Base case: region too small to divide, or small enough to be a room
             if width < 2 || height < 2 {
                 return;
             }
+            if width <= division.room_size && height <= division.room_size {
+                return;
+            }
 
-            // Choose orientation based on dimensions and complexity
-            let horizontal = if width < height {
-                true
-            } else if height < width {
-                false
-            } else {
-                // Equal dimensions: use complexity to decide
-                if complexity < 0.1 {
-                    true // Deterministic: prefer horizontal
-                } else {
-                    rng.gen_bool(0.5) // Random
+            // Choose orientation based on the heuristic, dimensions and complexity
+            let horizontal = match division.orientation {
+                Orientation::Horizontal => true,
+                Orientation::Vertical => false,
+                Orientation::Random => rng.gen_bool(0.5),
+                Orientation::Shape => {
+                    if width < height {
+                        true
+                    } else if height < width {
+                        false
+                    } else {
+                        // Equal dimensions: use complexity to decide
+                        if complexity < 0.1 {
+                            true // Deterministic: prefer horizontal
+                        } else {
+                            rng.gen_bool(0.5) // Random
+                        }
+                    }
                 }
             };
 
@@ -80,34 +276,18 @@ impl MazeGenerator for RecursiveDivision {
                     }
                 };
 
-                // Add wall (except for a gap)
-                let gap_x = if complexity < 0.1 {
-                    x1 + width / 2 // Deterministic: middle gap
-                } else {
-                    // Random gap position
-                    rng.gen_range(x1..x2)
-                };
+                // Add wall (except for the doors)
+                let doors = division.door_cells(x1, x2, rng);
 
                 for x in x1..x2 {
-                    if x != gap_x && wall_y > 0 && wall_y < maze.height() {
-                        // Add wall back by setting cell walls directly
-                        // We need to get cells separately to avoid borrow checker issues
-                        {
-                            if let Some(mut_c1) = maze.get_cell_mut(x, wall_y - 1) {
-                                mut_c1.south = true;
-                            }
-                        }
-                        {
-                            if let Some(mut_c2) = maze.get_cell_mut(x, wall_y) {
-                                mut_c2.north = true;
-                            }
-                        }
+                    if !doors.contains(&x) && wall_y > 0 && wall_y < maze.height() {
+                        maze.add_wall(x, wall_y - 1, x, wall_y);
                     }
                 }
 
                 // Recursively divide the two regions
-                divide(maze, rng, x1, y1, x2, wall_y, complexity);
-                divide(maze, rng, x1, wall_y, x2, y2, complexity);
+                divide(maze, rng, x1, y1, x2, wall_y, division);
+                divide(maze, rng, x1, wall_y, x2, y2, division);
             } else {
                 // Draw vertical wall
                 let wall_x = if complexity < 0.1 {
@@ -126,40 +306,21 @@ impl MazeGenerator for RecursiveDivision {
                     }
                 };
 
-                // Add wall (except for a gap)
-                let gap_y = if complexity < 0.1 {
-                    y1 + height / 2 // Deterministic: middle gap
-                } else {
-                    // Random gap position
-                    rng.gen_range(y1..y2)
-                };
+                // Add wall (except for the doors)
+                let doors = division.door_cells(y1, y2, rng);
 
                 for y in y1..y2 {
-                    if y != gap_y && wall_x > 0 && wall_x < maze.width() {
-                        // Add wall back
-                        // We need to get cells separately to avoid borrow checker issues
-                        {
-                            if let Some(mut_c1) = maze.get_cell_mut(wall_x - 1, y) {
-                                mut_c1.east = true;
-                            }
-                        }
-                        {
-                            if let Some(mut_c2) = maze.get_cell_mut(wall_x, y) {
-                                mut_c2.west = true;
-                            }
-                        }
+                    if !doors.contains(&y) && wall_x > 0 && wall_x < maze.width() {
+                        maze.add_wall(wall_x - 1, y, wall_x, y);
                     }
                 }
 
                 // Recursively divide the two regions
-                divide(maze, rng, x1, y1, wall_x, y2, complexity);
-                divide(maze, rng, wall_x, y1, x2, y2, complexity);
+                divide(maze, rng, x1, y1, wall_x, y2, division);
+                divide(maze, rng, wall_x, y1, x2, y2, division);
             }
         }
 
-        divide(&mut maze, &mut rng, 0, 0, width, height, complexity);
-
-        maze
+        divide(maze, rng, 0, 0, width, height, &division);
     }
 }
-
@@ -0,0 +1,99 @@
+use crate::algorithms::*;
+
+/// Generators available by name
+///
+/// The CLI, the config loader and the benches all look generators up here,
+/// and bindings such as the WASM `get_algorithms` should list them from here
+/// too. Crates that depend on this one can `register` their own generators.
+#[derive(Default)]
+pub struct Registry {
+    generators: Vec<Box<dyn MazeGenerator>>,
+}
+
+impl Registry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Registry {
+            generators: Vec::new(),
+        }
+    }
+
+    /// Create a registry holding every generator in this crate
+    pub fn builtin() -> Self {
+        let mut registry = Registry::new();
+        let generators: Vec<Box<dyn MazeGenerator>> = vec![
+            Box::new(RecursiveBacktracking),
+            Box::new(Kruskal::default()),
+            Box::new(Prim::default()),
+            Box::new(AldousBroder::default()),
+            Box::new(Wilsons::default()),
+            Box::new(RecursiveDivision::default()),
+            Box::new(GrowingTree::default()),
+            Box::new(HuntAndKill),
+            Box::new(BinaryTree),
+            Box::new(Sidewinder::default()),
+            Box::new(Eller),
+            Box::new(DfsIterative),
+            Box::new(Bfs),
+            Box::new(RecursiveBacktrackingBraided),
+            Box::new(CellularAutomata::default()),
+            Box::new(DrunkardsWalk),
+            Box::new(RandomObstacle),
+            Box::new(Hamiltonian::default()),
+            Box::new(Voronoi::default()),
+            Box::new(Dungeon::default()),
+            Box::new(OriginShift::default()),
+        ];
+        for generator in generators {
+            registry
+                .register(generator)
+                .expect("built-in generator names are unique");
+        }
+        registry
+    }
+
+    /// Add a generator
+    /// Fails if its name or one of its aliases already selects another generator.
+    pub fn register(&mut self, generator: Box<dyn MazeGenerator>) -> Result<(), String> {
+        let names = std::iter::once(generator.name()).chain(generator.aliases().iter().copied());
+        for name in names {
+            if let Some(existing) = self.get(name) {
+                return Err(format!(
+                    "Generator name {} is already used by {}",
+                    name,
+                    existing.name()
+                ));
+            }
+        }
+        self.generators.push(generator);
+        Ok(())
+    }
+
+    /// Look a generator up by name or alias
+    /// Matching ignores case and treats '-' and '_' alike.
+    pub fn get(&self, name: &str) -> Option<&dyn MazeGenerator> {
+        let wanted = normalize(name);
+        self.generators
+            .iter()
+            .find(|generator| {
+                normalize(generator.name()) == wanted
+                    || generator.aliases().iter().any(|&alias| normalize(alias) == wanted)
+            })
+            .map(|generator| generator.as_ref())
+    }
+
+    /// Every generator, in registration order
+    pub fn iter(&self) -> impl Iterator<Item = &dyn MazeGenerator> {
+        self.generators.iter().map(|generator| generator.as_ref())
+    }
+
+    /// Canonical names of every generator, in registration order
+    pub fn names(&self) -> Vec<&'static str> {
+        self.iter().map(|generator| generator.name()).collect()
+    }
+}
+
+/// Spelling used to compare generator names
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase().replace('-', "_")
+}
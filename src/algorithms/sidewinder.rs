@@ -1,21 +1,75 @@
-use crate::algorithms::MazeGenerator;
+use crate::algorithms::{GeneratorOption, MazeGenerator, Params};
 use crate::maze::Maze;
 use rand::Rng;
-use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 
-pub struct Sidewinder;
+/// Parameters for sidewinder; unset ones follow `complexity`
+#[derive(Debug, Clone, Default)]
+pub struct SidewinderParams {
+    /// Chance of closing a run at each cell
+    pub run_end: Option<f64>,
+}
+
+impl SidewinderParams {
+    /// Override the parameters named in `params`
+    pub fn apply(&mut self, params: &Params) -> Result<(), String> {
+        params.check_keys("sidewinder", &["run_end"])?;
+        if let Some(run_end) = params.get_probability("run_end")? {
+            self.run_end = Some(run_end);
+        }
+        Ok(())
+    }
+
+    /// The parameters that are set, as they would be written
+    pub fn to_params(&self) -> Params {
+        let mut params = Params::new();
+        if let Some(run_end) = &self.run_end {
+            params.set("run_end", &run_end.to_string());
+        }
+        params
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Sidewinder {
+    pub params: SidewinderParams,
+}
 
 impl MazeGenerator for Sidewinder {
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze {
-        let mut maze = Maze::new(width, height);
-        let mut rng = match seed {
-            Some(s) => ChaCha8Rng::seed_from_u64(s),
-            None => {
-                let seed = rand::thread_rng().gen();
-                ChaCha8Rng::seed_from_u64(seed)
-            }
-        };
+    fn name(&self) -> &'static str {
+        "sidewinder"
+    }
+
+    fn description(&self) -> &'static str {
+        "Row by row runs that each open one cell north; one open top row"
+    }
+
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[
+            GeneratorOption {
+                name: "complexity",
+                description: "Chance of ending a run; higher means shorter runs",
+            },
+            GeneratorOption {
+                name: "run_end",
+                description: "Chance of ending a run at each cell (0.0 to 1.0)",
+            },
+        ]
+    }
+
+    fn with_params(&self, params: &Params) -> Result<Box<dyn MazeGenerator>, String> {
+        let mut generator = self.clone();
+        generator.params.apply(params)?;
+        Ok(Box::new(generator))
+    }
+
+    fn params(&self) -> Params {
+        self.params.to_params()
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        let width = maze.width();
+        let height = maze.height();
 
         // Process row by row, top to bottom
         for y in 0..height {
@@ -31,7 +85,7 @@ impl MazeGenerator for Sidewinder {
                     // Probability of ending run increases with complexity
                     // At complexity 0.0: very long runs (low probability to end)
                     // At complexity 1.0: very short runs (high probability to end)
-                    let end_probability = 0.3 + complexity * 0.5; // Range: 0.3 to 0.8
+                    let end_probability = self.params.run_end.unwrap_or(0.3 + complexity * 0.5); // Range: 0.3 to 0.8
                     rng.gen::<f64>() < end_probability
                 };
 
@@ -61,8 +115,5 @@ impl MazeGenerator for Sidewinder {
                 }
             }
         }
-
-        maze
     }
 }
-
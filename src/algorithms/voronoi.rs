@@ -1,32 +1,89 @@
-use crate::algorithms::MazeGenerator;
+use crate::algorithms::{GeneratorOption, MazeGenerator, Params};
 use crate::maze::Maze;
 use rand::Rng;
-use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
-use std::collections::HashSet;
+use std::collections::BTreeSet;
 
-pub struct Voronoi;
+/// Parameters for Voronoi; unset ones follow `complexity`
+#[derive(Debug, Clone, Default)]
+pub struct VoronoiParams {
+    /// Number of seed points, one per region
+    pub seeds: Option<usize>,
+}
+
+impl VoronoiParams {
+    /// Override the parameters named in `params`
+    pub fn apply(&mut self, params: &Params) -> Result<(), String> {
+        params.check_keys("voronoi", &["seeds"])?;
+        if let Some(seeds) = params.get("seeds")? {
+            self.seeds = Some(seeds);
+        }
+        Ok(())
+    }
+
+    /// The parameters that are set, as they would be written
+    pub fn to_params(&self) -> Params {
+        let mut params = Params::new();
+        if let Some(seeds) = &self.seeds {
+            params.set("seeds", &seeds.to_string());
+        }
+        params
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Voronoi {
+    pub params: VoronoiParams,
+}
 
 impl MazeGenerator for Voronoi {
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze {
-        let mut maze = Maze::new(width, height);
-        let mut rng = match seed {
-            Some(s) => ChaCha8Rng::seed_from_u64(s),
-            None => {
-                let seed = rand::thread_rng().gen();
-                ChaCha8Rng::seed_from_u64(seed)
-            }
-        };
+    fn name(&self) -> &'static str {
+        "voronoi"
+    }
+
+    fn description(&self) -> &'static str {
+        "Regions around random seed points, joined by passages"
+    }
+
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[
+            GeneratorOption {
+                name: "complexity",
+                description: "Number of seed points; higher means smaller regions",
+            },
+            GeneratorOption {
+                name: "seeds",
+                description: "Number of seed points, at least 2 and at most one per cell",
+            },
+        ]
+    }
+
+    fn with_params(&self, params: &Params) -> Result<Box<dyn MazeGenerator>, String> {
+        let mut generator = self.clone();
+        generator.params.apply(params)?;
+        Ok(Box::new(generator))
+    }
+
+    fn params(&self) -> Params {
+        self.params.to_params()
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        let width = maze.width();
+        let height = maze.height();
 
         // Number of Voronoi points based on complexity
         // Lower complexity = fewer points (larger regions)
         // Higher complexity = more points (smaller regions)
-        let num_points = ((5.0 + complexity * 15.0) as usize).min((width * height / 4) as usize);
+        let num_points = match self.params.seeds {
+            Some(seeds) => seeds.min((width * height) as usize),
+            None => ((5.0 + complexity * 15.0) as usize).min((width * height / 4) as usize),
+        };
         let num_points = num_points.max(2);
 
         // Generate random points
         let mut points = Vec::new();
-        let mut point_set = HashSet::new();
+        let mut point_set = BTreeSet::new();
         for _ in 0..num_points {
             let mut x = rng.gen_range(0..width);
             let mut y = rng.gen_range(0..height);
@@ -63,7 +120,7 @@ impl MazeGenerator for Voronoi {
         }
 
         // Build graph: edges between adjacent Voronoi regions
-        let mut edges = HashSet::new();
+        let mut edges = BTreeSet::new();
         for y in 0..height {
             for x in 0..width {
                 let region1 = cell_to_point[y as usize][x as usize];
@@ -207,8 +264,7 @@ impl MazeGenerator for Voronoi {
                 }
 
                 // Carve selected passages
-                for i in 0..num_passages.min(boundary_cells.len()) {
-                    let (x1, y1, x2, y2) = boundary_cells[i];
+                for &(x1, y1, x2, y2) in boundary_cells.iter().take(num_passages) {
                     maze.remove_wall(x1, y1, x2, y2);
                 }
             }
@@ -216,8 +272,5 @@ impl MazeGenerator for Voronoi {
 
         // Ensure connectivity from start to end
         maze.ensure_connectivity();
-
-        maze
     }
 }
-
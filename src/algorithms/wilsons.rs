@@ -1,108 +1,213 @@
-use crate::algorithms::MazeGenerator;
+use crate::algorithms::{GenerateError, GeneratorOption, MazeGenerator, Params};
+use crate::grid::{ensure_connectivity, CellId, Grid};
 use crate::maze::Maze;
 use rand::Rng;
-use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 use std::collections::{HashSet, VecDeque};
 
-pub struct Wilsons;
+/// Parameters for wilsons
+#[derive(Debug, Clone, Default)]
+pub struct WilsonsParams {
+    /// Sample every spanning tree with equal probability, ignoring `complexity`
+    pub uniform: Option<bool>,
+}
+
+impl WilsonsParams {
+    /// Override the parameters named in `params`
+    pub fn apply(&mut self, params: &Params) -> Result<(), String> {
+        params.check_keys("wilsons", &["uniform"])?;
+        if let Some(uniform) = params.get("uniform")? {
+            self.uniform = Some(uniform);
+        }
+        Ok(())
+    }
+
+    /// The parameters that are set, as they would be written
+    pub fn to_params(&self) -> Params {
+        let mut params = Params::new();
+        if let Some(uniform) = &self.uniform {
+            params.set("uniform", &uniform.to_string());
+        }
+        params
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Wilsons {
+    pub params: WilsonsParams,
+}
 
 impl MazeGenerator for Wilsons {
-    fn generate(&self, width: u32, height: u32, complexity: f64, seed: Option<u64>) -> Maze {
-        let mut maze = Maze::new(width, height);
-        let mut rng = match seed {
-            Some(s) => ChaCha8Rng::seed_from_u64(s),
-            None => {
-                let seed = rand::thread_rng().gen();
-                ChaCha8Rng::seed_from_u64(seed)
-            }
-        };
-        let mut in_tree = HashSet::new();
-
-        // Start with one random cell in the tree
-        let start_x = rng.gen_range(0..width);
-        let start_y = rng.gen_range(0..height);
-        in_tree.insert((start_x, start_y));
-
-        // List of unvisited cells
-        let mut unvisited: Vec<(u32, u32)> = (0..height)
-            .flat_map(|y| (0..width).map(move |x| (x, y)))
-            .filter(|&(x, y)| !in_tree.contains(&(x, y)))
-            .collect();
-
-        // Shuffle unvisited cells for randomness
-        if complexity > 0.0 {
-            for i in 0..unvisited.len() {
-                let j = rng.gen_range(i..unvisited.len());
-                unvisited.swap(i, j);
-            }
+    fn name(&self) -> &'static str {
+        "wilsons"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["wilson"]
+    }
+
+    fn description(&self) -> &'static str {
+        "Loop-erased random walks joined onto the maze; unbiased"
+    }
+
+    fn options(&self) -> &'static [GeneratorOption] {
+        &[
+            GeneratorOption {
+                name: "complexity",
+                description: "Randomness of the walk and of the order cells are added",
+            },
+            GeneratorOption {
+                name: "uniform",
+                description: "true samples every spanning tree with equal probability, ignoring complexity",
+            },
+        ]
+    }
+
+    fn with_params(&self, params: &Params) -> Result<Box<dyn MazeGenerator>, String> {
+        let mut generator = self.clone();
+        generator.params.apply(params)?;
+        Ok(Box::new(generator))
+    }
+
+    fn params(&self) -> Params {
+        self.params.to_params()
+    }
+
+    fn carve(&self, maze: &mut Maze, complexity: f64, rng: &mut ChaCha8Rng) {
+        carve_tree(maze, self.complexity(complexity), rng);
+    }
+
+    fn carve_grid(
+        &self,
+        grid: &mut dyn Grid,
+        complexity: f64,
+        rng: &mut ChaCha8Rng,
+    ) -> Result<(), GenerateError> {
+        carve_tree(grid, self.complexity(complexity), rng);
+        // Grids can refuse links on a finished walk, like a weave crossing's sides
+        ensure_connectivity(grid);
+        Ok(())
+    }
+
+    fn supports_mask(&self) -> bool {
+        true
+    }
+
+    fn supports_grids(&self) -> bool {
+        true
+    }
+}
+
+impl Wilsons {
+    /// The complexity to walk with, or None for a plain uniform random walk
+    fn complexity(&self, complexity: f64) -> Option<f64> {
+        (self.params.uniform != Some(true)).then_some(complexity)
+    }
+}
+
+/// Steps per cell the walks may take between them when they prefer their first neighbour
+/// Such a walk can circle one corner of the grid without reaching the tree,
+/// so past this the cells still outside are joined with `ensure_connectivity`.
+const DRIFT_STEPS_PER_CELL: usize = 100;
+
+fn carve_tree(grid: &mut dyn Grid, complexity: Option<f64>, rng: &mut ChaCha8Rng) {
+    let mut in_tree = HashSet::new();
+    let drifts = complexity.is_some_and(|c| c < 0.1);
+    let mut steps_left = grid.active_count() * DRIFT_STEPS_PER_CELL;
+
+    // Start with one random cell in the tree
+    let start = grid.random_cell(rng);
+    in_tree.insert(start);
+    grid.visit(start);
+
+    // List of unvisited cells
+    let mut unvisited: Vec<CellId> = grid
+        .cells()
+        .into_iter()
+        .filter(|cell| !in_tree.contains(cell))
+        .collect();
+
+    // Shuffle unvisited cells for randomness
+    if complexity.is_none_or(|c| c > 0.0) {
+        for i in 0..unvisited.len() {
+            let j = rng.gen_range(i..unvisited.len());
+            unvisited.swap(i, j);
         }
+    }
 
-        // For each unvisited cell, perform loop-erased random walk
-        while !unvisited.is_empty() {
-            // Pick an unvisited cell to start the walk
-            let start_cell = unvisited[0];
-            let mut current = start_cell;
-            let mut path = VecDeque::new();
-            path.push_back(current);
-            let mut visited_in_walk = HashSet::new();
-            visited_in_walk.insert(current);
-
-            // Random walk until we hit the tree
-            while !in_tree.contains(&current) {
-                let neighbors = maze.get_neighbors(current.0, current.1);
-                
-                // Choose neighbor based on complexity
-                let next_idx = if neighbors.is_empty() {
-                    break;
-                } else if neighbors.len() == 1 {
+    // For each unvisited cell, perform loop-erased random walk
+    'walks: while !unvisited.is_empty() {
+        // Pick an unvisited cell to start the walk
+        let start_cell = unvisited[0];
+        let mut current = start_cell;
+        let mut path = VecDeque::new();
+        path.push_back(current);
+        let mut visited_in_walk = HashSet::new();
+        visited_in_walk.insert(current);
+        grid.visit(current);
+
+        // Random walk until we hit the tree
+        while !in_tree.contains(&current) {
+            if drifts && steps_left == 0 {
+                // Drop the unfinished walk and join what is left below
+                for &cell in path.iter().rev() {
+                    grid.backtrack(cell);
+                }
+                ensure_connectivity(grid);
+                break 'walks;
+            }
+            steps_left = steps_left.saturating_sub(1);
+            let neighbors = grid.neighbors(current);
+
+            // Choose neighbor based on complexity
+            let next_idx = if neighbors.is_empty() {
+                break;
+            } else if neighbors.len() == 1 {
+                0
+            } else if drifts {
+                // Very low complexity: prefer first neighbor
+                if rng.gen::<f64>() < 0.8 {
                     0
-                } else if complexity < 0.1 {
-                    // Very low complexity: prefer first neighbor
-                    if rng.gen::<f64>() < 0.8 {
-                        0
-                    } else {
-                        rng.gen_range(1..neighbors.len())
-                    }
                 } else {
-                    // Higher complexity: fully random
-                    rng.gen_range(0..neighbors.len())
-                };
-                
-                let next = neighbors[next_idx];
-
-                // If we've visited this cell in the current walk, erase the loop
-                if visited_in_walk.contains(&next) {
-                    // Remove all cells after the first occurrence of 'next' in the path
-                    while let Some(back) = path.back() {
-                        if *back == next {
-                            break;
-                        }
-                        visited_in_walk.remove(back);
-                        path.pop_back();
+                    rng.gen_range(1..neighbors.len())
+                }
+            } else {
+                // Higher complexity, or uniform: fully random
+                rng.gen_range(0..neighbors.len())
+            };
+
+            let next = neighbors[next_idx];
+            grid.visit(next);
+
+            // If we've visited this cell in the current walk, erase the loop
+            if visited_in_walk.contains(&next) {
+                // Remove all cells after the first occurrence of 'next' in the path
+                while let Some(back) = path.back() {
+                    if *back == next {
+                        break;
                     }
-                    current = next;
-                } else {
-                    path.push_back(next);
-                    visited_in_walk.insert(next);
-                    current = next;
+                    grid.backtrack(*back);
+                    visited_in_walk.remove(back);
+                    path.pop_back();
                 }
+                current = next;
+            } else {
+                path.push_back(next);
+                visited_in_walk.insert(next);
+                current = next;
             }
+        }
 
-            // Add the path to the tree
-            let mut prev = path.pop_front().unwrap();
-            while let Some(current) = path.pop_front() {
-                maze.remove_wall(prev.0, prev.1, current.0, current.1);
-                in_tree.insert(prev);
-                prev = current;
-            }
+        // Add the path to the tree
+        let mut prev = path.pop_front().unwrap();
+        while let Some(current) = path.pop_front() {
+            grid.link(prev, current);
             in_tree.insert(prev);
-
-            // Remove visited cells from unvisited list
-            unvisited.retain(|&cell| !in_tree.contains(&cell));
+            prev = current;
         }
+        in_tree.insert(prev);
 
-        maze
+        // Remove visited cells from unvisited list
+        unvisited.retain(|cell| !in_tree.contains(cell));
     }
 }
-
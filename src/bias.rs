@@ -0,0 +1,84 @@
+use crate::grid::{Axis, CellId, Grid, GridWrapper};
+
+/// A grid wrapper that weights passages by direction
+///
+/// A positive bias favours horizontal passages and a negative one vertical
+/// passages, so generators that follow `Grid::weight` carve long horizontal
+/// runs or tall vertical shafts. At 1.0 or -1.0 the other direction is only
+/// taken when nothing else is left. Passages that run neither way, like
+/// stairs, keep an even weight.
+pub struct Biased<'a> {
+    grid: &'a mut dyn Grid,
+    bias: f64,
+}
+
+impl<'a> Biased<'a> {
+    /// Wrap `grid` with a bias from -1.0 (vertical) to 1.0 (horizontal)
+    pub fn new(grid: &'a mut dyn Grid, bias: f64) -> Self {
+        Biased {
+            grid,
+            bias: bias.clamp(-1.0, 1.0),
+        }
+    }
+}
+
+/// Parse a bias as a number from -1.0 to 1.0, or as "horizontal" or "vertical"
+/// with an optional strength, e.g. "horizontal:0.8"
+pub fn parse_bias(s: &str) -> Result<f64, String> {
+    let (name, strength) = match s.split_once(':') {
+        Some((name, strength)) => (name.trim(), Some(strength.trim())),
+        None => (s.trim(), None),
+    };
+    let strength = |default: f64| -> Result<f64, String> {
+        strength
+            .map(|v| v.parse::<f64>().map_err(|e| format!("Invalid bias strength: {} ({})", s, e)))
+            .unwrap_or(Ok(default))
+    };
+
+    let bias = match name.to_lowercase().as_str() {
+        "horizontal" | "h" => strength(0.5)?,
+        "vertical" | "v" => -strength(0.5)?,
+        "none" => 0.0,
+        _ => name
+            .parse::<f64>()
+            .map_err(|_| format!("Unknown bias: {} (use -1.0 to 1.0, horizontal or vertical)", s))?,
+    };
+    if (-1.0..=1.0).contains(&bias) {
+        Ok(bias)
+    } else {
+        Err(format!("Bias must be between -1.0 and 1.0, got: {}", s))
+    }
+}
+
+/// Describe a bias for the settings summary, e.g. "0.50 (horizontal)"
+pub fn describe_bias(bias: f64) -> String {
+    let direction = if bias > 0.0 {
+        "horizontal"
+    } else if bias < 0.0 {
+        "vertical"
+    } else {
+        "none"
+    };
+    format!("{:.2} ({})", bias, direction)
+}
+
+impl GridWrapper for Biased<'_> {
+    fn inner(&self) -> &dyn Grid {
+        self.grid
+    }
+
+    fn inner_mut(&mut self) -> &mut dyn Grid {
+        self.grid
+    }
+
+    /// Scales any weight of the wrapped grid by the passage's direction
+    fn weight(&self, a: CellId, b: CellId) -> Option<f64> {
+        let base = self.grid.weight(a, b).unwrap_or(1.0);
+        let factor = match self.grid.axis(a, b) {
+            Some(Axis::Horizontal) => 1.0 + self.bias,
+            Some(Axis::Vertical) => 1.0 - self.bias,
+            None => 1.0,
+        };
+        Some(base * factor)
+    }
+}
@@ -0,0 +1,48 @@
+/// A fixed-size set of bits packed into 64-bit words
+#[derive(Clone, Debug)]
+pub(crate) struct BitSet {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitSet {
+    /// Create a bitset of `len` bits, all set to `value`
+    pub fn new(len: usize, value: bool) -> Self {
+        let fill = if value { u64::MAX } else { 0 };
+        let mut bits = BitSet {
+            words: vec![fill; len.div_ceil(64)],
+            len,
+        };
+        bits.clear_padding();
+        bits
+    }
+
+    /// Get the bit at `index`
+    pub fn get(&self, index: usize) -> bool {
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// Set the bit at `index` to `value`
+    pub fn set(&mut self, index: usize, value: bool) {
+        if value {
+            self.words[index / 64] |= 1 << (index % 64);
+        } else {
+            self.words[index / 64] &= !(1 << (index % 64));
+        }
+    }
+
+    /// Count the bits that are set
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Keep the unused bits of the last word cleared so `count_ones` stays exact
+    fn clear_padding(&mut self) {
+        let used = self.len % 64;
+        if used != 0 {
+            if let Some(last) = self.words.last_mut() {
+                *last &= (1 << used) - 1;
+            }
+        }
+    }
+}
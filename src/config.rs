@@ -1,3 +1,9 @@
+use crate::algorithms::composite::Layout;
+use crate::algorithms::{Params, Registry};
+use crate::bias::parse_bias;
+use crate::maze::{Endpoint, Maze, Side, Wrap};
+use crate::polar::PolarEntrance;
+use crate::postprocess::Pipeline;
 use std::fs;
 use std::path::Path;
 
@@ -6,11 +12,7 @@ use std::path::Path;
 /// Returns (r, g, b) as u8 values
 pub fn parse_hex_color(hex: &str) -> Result<[u8; 3], String> {
     let hex = hex.trim();
-    let hex = if hex.starts_with('#') {
-        &hex[1..]
-    } else {
-        hex
-    };
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
 
     if hex.len() != 6 {
         return Err(format!("Hex color must be 6 digits, got: {}", hex));
@@ -26,91 +28,304 @@ pub fn parse_hex_color(hex: &str) -> Result<[u8; 3], String> {
     Ok([r, g, b])
 }
 
-#[derive(Debug, Clone)]
-pub enum Algorithm {
-    RecursiveBacktracking,
-    Kruskal,
-    Prim,
-    AldousBroder,
-    Wilsons,
-    RecursiveDivision,
-    GrowingTree,
-    HuntAndKill,
-    BinaryTree,
-    Sidewinder,
-    Eller,
-    DfsIterative,
-    Bfs,
-    RecursiveBacktrackingBraided,
-    CellularAutomata,
-    DrunkardsWalk,
-    RandomObstacle,
-    Hamiltonian,
-    Voronoi,
+/// Parse an openings preset: corners, left-right or top-bottom
+pub fn parse_openings(s: &str) -> Result<Openings, String> {
+    Openings::from_str(s).ok_or_else(|| format!("Unknown openings preset: {}", s))
+}
+
+/// A cell position for the entrance or exit, as written in config or on the CLI
+/// Accepts formats: "x,y" or "x,y,side" where side is north/south/east/west
+/// (or top/bottom/right/left)
+#[derive(Debug, Clone, Copy)]
+pub struct EndpointSpec {
+    pub x: u32,
+    pub y: u32,
+    pub side: Option<Side>,
+}
+
+impl EndpointSpec {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = s.split(',').map(|p| p.trim()).collect();
+        if parts.len() < 2 || parts.len() > 3 {
+            return Err(format!("Endpoint must be \"x,y\" or \"x,y,side\", got: {}", s));
+        }
+
+        let x = parts[0]
+            .parse()
+            .map_err(|e| format!("Invalid endpoint x coordinate: {}", e))?;
+        let y = parts[1]
+            .parse()
+            .map_err(|e| format!("Invalid endpoint y coordinate: {}", e))?;
+        let side = match parts.get(2) {
+            Some(side) => {
+                Some(Side::parse(side).ok_or_else(|| format!("Unknown endpoint side: {}", side))?)
+            }
+            None => None,
+        };
+
+        Ok(EndpointSpec { x, y, side })
+    }
+
+    /// Resolve against the maze the endpoint will be placed in
+    /// Without an explicit side, a border cell opens the first border wall it
+    /// touches (west, east, north, south) and an interior cell opens nothing.
+    pub fn resolve(&self, maze: &Maze) -> Result<Endpoint, String> {
+        if !maze.is_active(self.x, self.y) {
+            return Err(format!(
+                "Endpoint ({}, {}) is outside the maze",
+                self.x, self.y
+            ));
+        }
+
+        let side = self.side.or_else(|| {
+            [Side::West, Side::East, Side::North, Side::South]
+                .into_iter()
+                .find(|&side| maze.is_border(self.x, self.y, side))
+        });
+
+        let endpoint = Endpoint::new(self.x, self.y, side);
+        if maze.accepts_endpoint(&endpoint) {
+            Ok(endpoint)
+        } else {
+            Err(format!(
+                "Endpoint ({}, {}) has no {} border wall",
+                self.x,
+                self.y,
+                side.map(|s| s.name()).unwrap_or("")
+            ))
+        }
+    }
 }
 
-impl Algorithm {
+/// Named entrance and exit layouts
+#[derive(Debug, Clone, Copy)]
+pub enum Openings {
+    /// Top of the top-left cell to the bottom of the bottom-right cell
+    Corners,
+    /// Middle of the left edge to the middle of the right edge
+    LeftRight,
+    /// Middle of the top edge to the middle of the bottom edge
+    TopBottom,
+}
+
+impl Openings {
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
-            "recursive_backtracking" | "recursive-backtracking" => Some(Algorithm::RecursiveBacktracking),
-            "kruskal" => Some(Algorithm::Kruskal),
-            "prim" => Some(Algorithm::Prim),
-            "aldous_broder" | "aldous-broder" => Some(Algorithm::AldousBroder),
-            "wilsons" | "wilson" => Some(Algorithm::Wilsons),
-            "recursive_division" | "recursive-division" => Some(Algorithm::RecursiveDivision),
-            "growing_tree" | "growing-tree" => Some(Algorithm::GrowingTree),
-            "hunt_and_kill" | "hunt-and-kill" => Some(Algorithm::HuntAndKill),
-            "binary_tree" | "binary-tree" => Some(Algorithm::BinaryTree),
-            "sidewinder" => Some(Algorithm::Sidewinder),
-            "eller" | "ellers" => Some(Algorithm::Eller),
-            "dfs_iterative" | "dfs-iterative" => Some(Algorithm::DfsIterative),
-            "bfs" => Some(Algorithm::Bfs),
-            "recursive_backtracking_braided" | "recursive-backtracking-braided" => Some(Algorithm::RecursiveBacktrackingBraided),
-            "cellular_automata" | "cellular-automata" => Some(Algorithm::CellularAutomata),
-            "drunkards_walk" | "drunkards-walk" => Some(Algorithm::DrunkardsWalk),
-            "random_obstacle" | "random-obstacle" => Some(Algorithm::RandomObstacle),
-            "hamiltonian" => Some(Algorithm::Hamiltonian),
-            "voronoi" => Some(Algorithm::Voronoi),
+            "corners" => Some(Openings::Corners),
+            "left_right" | "left-right" => Some(Openings::LeftRight),
+            "top_bottom" | "top-bottom" => Some(Openings::TopBottom),
             _ => None,
         }
     }
 
-    pub fn to_string(&self) -> &'static str {
+    /// Start and goal for a maze
+    /// On a masked maze each opening moves inwards to the first active cell,
+    /// and the middle row or column moves to the nearest one with active cells.
+    pub fn endpoints(&self, maze: &Maze) -> (Endpoint, Endpoint) {
+        let (mut start, mut goal) = (maze.start(), maze.goal());
+        let (width, height) = (maze.width(), maze.height());
+
         match self {
-            Algorithm::RecursiveBacktracking => "recursive_backtracking",
-            Algorithm::Kruskal => "kruskal",
-            Algorithm::Prim => "prim",
-            Algorithm::AldousBroder => "aldous_broder",
-            Algorithm::Wilsons => "wilsons",
-            Algorithm::RecursiveDivision => "recursive_division",
-            Algorithm::GrowingTree => "growing_tree",
-            Algorithm::HuntAndKill => "hunt_and_kill",
-            Algorithm::BinaryTree => "binary_tree",
-            Algorithm::Sidewinder => "sidewinder",
-            Algorithm::Eller => "eller",
-            Algorithm::DfsIterative => "dfs_iterative",
-            Algorithm::Bfs => "bfs",
-            Algorithm::RecursiveBacktrackingBraided => "recursive_backtracking_braided",
-            Algorithm::CellularAutomata => "cellular_automata",
-            Algorithm::DrunkardsWalk => "drunkards_walk",
-            Algorithm::RandomObstacle => "random_obstacle",
-            Algorithm::Hamiltonian => "hamiltonian",
-            Algorithm::Voronoi => "voronoi",
+            Openings::Corners => {
+                let cells = maze.active_cells();
+                if let (Some(&(sx, sy)), Some(&(gx, gy))) = (cells.first(), cells.last()) {
+                    start = Endpoint::new(sx, sy, Some(Side::North));
+                    goal = Endpoint::new(gx, gy, Some(Side::South));
+                }
+            }
+            Openings::LeftRight => {
+                let row = nearest_to_middle(height)
+                    .find(|&y| (0..width).any(|x| maze.is_active(x, y)));
+                if let Some(y) = row {
+                    let first = (0..width).find(|&x| maze.is_active(x, y)).unwrap_or(0);
+                    let last = (0..width).rev().find(|&x| maze.is_active(x, y)).unwrap_or(0);
+                    start = Endpoint::new(first, y, Some(Side::West));
+                    goal = Endpoint::new(last, y, Some(Side::East));
+                }
+            }
+            Openings::TopBottom => {
+                let column = nearest_to_middle(width)
+                    .find(|&x| (0..height).any(|y| maze.is_active(x, y)));
+                if let Some(x) = column {
+                    let first = (0..height).find(|&y| maze.is_active(x, y)).unwrap_or(0);
+                    let last = (0..height).rev().find(|&y| maze.is_active(x, y)).unwrap_or(0);
+                    start = Endpoint::new(x, first, Some(Side::North));
+                    goal = Endpoint::new(x, last, Some(Side::South));
+                }
+            }
+        }
+
+        (start, goal)
+    }
+}
+
+/// Indices 0..len ordered by distance from len / 2, middle first
+fn nearest_to_middle(len: u32) -> impl Iterator<Item = u32> {
+    let middle = len / 2;
+    (0..len).flat_map(move |offset| {
+        let before = middle.checked_sub(offset).filter(|_| offset > 0);
+        let after = Some(middle + offset).filter(|&i| i < len);
+        before.into_iter().chain(after)
+    })
+}
+
+/// Cell shape of the maze
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridKind {
+    Square,
+    Hex,
+    Delta,
+    Polar,
+    Weave,
+}
+
+impl GridKind {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "square" => Some(GridKind::Square),
+            "hex" | "hexagonal" => Some(GridKind::Hex),
+            "delta" | "triangle" | "triangular" => Some(GridKind::Delta),
+            "polar" | "circular" | "theta" => Some(GridKind::Polar),
+            "weave" => Some(GridKind::Weave),
+            _ => None,
         }
     }
 }
 
+/// Parse a cell shape: square, hex, delta, polar or weave
+pub fn parse_grid(s: &str) -> Result<GridKind, String> {
+    GridKind::from_str(s).ok_or_else(|| format!("Unknown grid: {}", s))
+}
+
+/// Parse where a polar maze is entered: center or rim
+pub fn parse_polar_entrance(s: &str) -> Result<PolarEntrance, String> {
+    PolarEntrance::parse(s).ok_or_else(|| format!("Unknown polar_entrance: {}", s))
+}
+
+/// Parse how the maze edges join: none, cylinder or torus
+pub fn parse_wrap(s: &str) -> Result<Wrap, String> {
+    Wrap::parse(s).ok_or_else(|| format!("Unknown wrap: {}", s))
+}
+
+/// One region of a composite layout, from a `[[composite.regions]]` entry
+#[derive(Debug, Clone)]
+pub struct RegionConfig {
+    /// Canonical name of a generator in the registry
+    pub algorithm: String,
+    pub params: Params,
+    /// Complexity for this region, in place of the maze's own
+    pub complexity: Option<f64>,
+    /// Mask file with the cells of this region, for the zones layout
+    pub zone: Option<String>,
+}
+
+/// Regions carved by different generators, from the `[composite]` table
+#[derive(Debug, Clone)]
+pub struct CompositeConfig {
+    pub layout: Layout,
+    /// Openings between each pair of neighbouring regions
+    pub passages: u32,
+    pub regions: Vec<RegionConfig>,
+}
+
+impl CompositeConfig {
+    /// Parse the `[composite]` table; every region must name a generator in `registry`
+    fn parse(table: &toml::Value, registry: &Registry) -> Result<Self, String> {
+        let layout = match table.get("layout").and_then(|v| v.as_str()) {
+            Some(layout) => layout.parse()?,
+            None => Layout::Bands,
+        };
+        let passages = match table.get("passages").and_then(|v| v.as_integer()) {
+            Some(passages) if passages >= 1 => passages as u32,
+            Some(passages) => return Err(format!("passages must be at least 1, got: {}", passages)),
+            None => 1,
+        };
+
+        let entries = table
+            .get("regions")
+            .and_then(|v| v.as_array())
+            .ok_or("composite needs a list of [[composite.regions]]")?;
+        let mut regions = Vec::new();
+        for entry in entries {
+            let name = entry
+                .get("algorithm")
+                .and_then(|v| v.as_str())
+                .ok_or("Each composite region needs an algorithm")?;
+            let algorithm = registry
+                .get(name)
+                .map(|generator| generator.name().to_string())
+                .ok_or_else(|| format!("Unknown algorithm: {}", name))?;
+            let complexity = entry
+                .get("complexity")
+                .and_then(|v| v.as_float())
+                .map(|c| c.clamp(0.0, 1.0));
+            let zone = entry.get("zone").and_then(|v| v.as_str()).map(str::to_string);
+            regions.push(RegionConfig {
+                algorithm,
+                params: params_table(entry),
+                complexity,
+                zone,
+            });
+        }
+        if regions.is_empty() {
+            return Err("composite needs at least one region".to_string());
+        }
+
+        Ok(CompositeConfig {
+            layout,
+            passages,
+            regions,
+        })
+    }
+}
+
+/// Parameters from the `params` table of `value`, if it has one
+fn params_table(value: &toml::Value) -> Params {
+    let mut params = Params::new();
+    if let Some(table) = value.get("params").and_then(|v| v.as_table()) {
+        for (key, value) in table {
+            match value {
+                toml::Value::String(s) => params.set(key, s),
+                other => params.set(key, &other.to_string()),
+            }
+        }
+    }
+    params
+}
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub width: u32,
     pub height: u32,
-    pub algorithm: Algorithm,
+    /// Canonical name of a generator in the registry
+    pub algorithm: String,
+    /// Parameters for the algorithm, from `[algorithm.params]` and `--param`
+    pub params: Params,
+    /// Regions carved by different generators, in place of the algorithm
+    pub composite: Option<CompositeConfig>,
     pub complexity: f64,
+    /// Directional bias from -1.0 (vertical shafts) to 1.0 (horizontal runs)
+    pub bias: f64,
     pub output: String,
     pub cell_size: u32,
     pub seed: Option<u64>,
     pub solution_line_color: String,
     pub solution_line_thickness: f32,
+    pub openings: Openings,
+    pub start: Option<EndpointSpec>,
+    pub goal: Option<EndpointSpec>,
+    pub mask: Option<String>,
+    /// Density map that sets the complexity of each region, e.g. "image:density.png"
+    pub density: Option<String>,
+    pub grid: GridKind,
+    pub polar_entrance: PolarEntrance,
+    pub levels: u32,
+    pub wrap: Wrap,
+    pub tiles: u32,
+    /// Where to write the generation event log, one event per line
+    pub events: Option<String>,
+    /// Post-processing steps run after generation, such as "braid:0.5"
+    pub post: Vec<String>,
 }
 
 impl Default for Config {
@@ -118,20 +333,36 @@ impl Default for Config {
         Config {
             width: 50,
             height: 50,
-            algorithm: Algorithm::RecursiveBacktracking,
+            algorithm: "recursive_backtracking".to_string(),
+            params: Params::new(),
+            composite: None,
             complexity: 0.5,
+            bias: 0.0,
             output: "maze.png".to_string(),
             cell_size: 10,
             seed: None,
             solution_line_color: "#ff0000".to_string(),
             solution_line_thickness: 0.33,
+            openings: Openings::Corners,
+            start: None,
+            goal: None,
+            mask: None,
+            density: None,
+            grid: GridKind::Square,
+            polar_entrance: PolarEntrance::Center,
+            levels: 1,
+            wrap: Wrap::None,
+            tiles: 1,
+            events: None,
+            post: Vec::new(),
         }
     }
 }
 
 impl Config {
     /// Load configuration from a TOML file
-    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+    /// The algorithm must name a generator in `registry`.
+    pub fn from_file<P: AsRef<Path>>(path: P, registry: &Registry) -> Result<Self, String> {
         let content = fs::read_to_string(path.as_ref())
             .map_err(|e| format!("Failed to read config file: {}", e))?;
 
@@ -148,13 +379,38 @@ impl Config {
             config.height = height as u32;
         }
 
-        if let Some(algorithm) = parsed.get("algorithm").and_then(|v| v.as_str()) {
-            config.algorithm = Algorithm::from_str(algorithm)
-                .ok_or_else(|| format!("Unknown algorithm: {}", algorithm))?;
+        // Either `algorithm = "name"` or an `[algorithm]` table with a name and params
+        let algorithm = parsed.get("algorithm");
+        let name = match algorithm {
+            Some(toml::Value::Table(table)) => table.get("name").and_then(|v| v.as_str()),
+            other => other.and_then(|v| v.as_str()),
+        };
+        if let Some(name) = name {
+            config.algorithm = registry
+                .get(name)
+                .map(|generator| generator.name().to_string())
+                .ok_or_else(|| format!("Unknown algorithm: {}", name))?;
+        }
+        if let Some(algorithm) = algorithm {
+            config.params = params_table(algorithm);
+        }
+
+        if let Some(composite) = parsed.get("composite") {
+            config.composite = Some(CompositeConfig::parse(composite, registry)?);
         }
 
         if let Some(complexity) = parsed.get("complexity").and_then(|v| v.as_float()) {
-            config.complexity = complexity.max(0.0).min(1.0);
+            config.complexity = complexity.clamp(0.0, 1.0);
+        }
+
+        // A number from -1.0 to 1.0, or "horizontal" / "vertical" with an optional strength
+        if let Some(bias) = parsed.get("bias") {
+            config.bias = match bias {
+                toml::Value::Float(f) => parse_bias(&f.to_string())?,
+                toml::Value::Integer(i) => parse_bias(&i.to_string())?,
+                toml::Value::String(s) => parse_bias(s)?,
+                _ => return Err("bias must be a number or a direction".to_string()),
+            };
         }
 
         if let Some(output) = parsed.get("output").and_then(|v| v.as_str()) {
@@ -180,23 +436,100 @@ impl Config {
 
         if let Some(line_thickness) = parsed.get("line_thickness").and_then(|v| v.as_float()) {
             let thickness = line_thickness as f32;
-            if thickness >= 0.0 && thickness <= 1.0 {
+            if (0.0..=1.0).contains(&thickness) {
                 config.solution_line_thickness = thickness;
             } else {
                 return Err("line_thickness must be between 0.0 and 1.0".to_string());
             }
         }
 
+        if let Some(openings) = parsed.get("openings").and_then(|v| v.as_str()) {
+            config.openings = parse_openings(openings)?;
+        }
+
+        if let Some(start) = parsed.get("start").and_then(|v| v.as_str()) {
+            config.start = Some(EndpointSpec::parse(start)?);
+        }
+
+        if let Some(goal) = parsed.get("goal").and_then(|v| v.as_str()) {
+            config.goal = Some(EndpointSpec::parse(goal)?);
+        }
+
+        if let Some(mask) = parsed.get("mask").and_then(|v| v.as_str()) {
+            config.mask = Some(mask.to_string());
+        }
+
+        if let Some(density) = parsed.get("density").and_then(|v| v.as_str()) {
+            config.density = Some(density.to_string());
+        }
+
+        if let Some(grid) = parsed.get("grid").and_then(|v| v.as_str()) {
+            config.grid = parse_grid(grid)?;
+        }
+
+        if let Some(entrance) = parsed.get("polar_entrance").and_then(|v| v.as_str()) {
+            config.polar_entrance = parse_polar_entrance(entrance)?;
+        }
+
+        if let Some(levels) = parsed.get("levels").and_then(|v| v.as_integer()) {
+            config.levels = (levels as u32).max(1);
+        }
+
+        if let Some(wrap) = parsed.get("wrap").and_then(|v| v.as_str()) {
+            config.wrap = parse_wrap(wrap)?;
+        }
+
+        if let Some(tiles) = parsed.get("tiles").and_then(|v| v.as_integer()) {
+            config.tiles = (tiles as u32).max(1);
+        }
+
+        if let Some(events) = parsed.get("events").and_then(|v| v.as_str()) {
+            config.events = Some(events.to_string());
+        }
+
+        // A single step as a string, or a list of steps run in order
+        if let Some(post) = parsed.get("post") {
+            let steps: Vec<String> = match post {
+                toml::Value::Array(steps) => steps
+                    .iter()
+                    .map(|step| step.as_str().map(str::to_string))
+                    .collect::<Option<_>>()
+                    .ok_or("post must be a list of strings")?,
+                other => vec![other.as_str().ok_or("post must be a list of strings")?.to_string()],
+            };
+            Pipeline::parse(&steps)?;
+            config.post = steps;
+        }
+
         Ok(config)
     }
 
+    /// Resolve the entrance and exit for the maze about to be generated
+    /// Explicit `start`/`goal` settings override the openings preset.
+    pub fn endpoints(&self, maze: &Maze) -> Result<(Endpoint, Endpoint), String> {
+        let (mut start, mut goal) = self.openings.endpoints(maze);
+        // Preset sides that lead across the seam of a wrapped maze open nothing
+        for endpoint in [&mut start, &mut goal] {
+            if !maze.accepts_endpoint(endpoint) {
+                endpoint.side = None;
+            }
+        }
+        if let Some(spec) = &self.start {
+            start = spec.resolve(maze)?;
+        }
+        if let Some(spec) = &self.goal {
+            goal = spec.resolve(maze)?;
+        }
+        Ok((start, goal))
+    }
+
     /// Load configuration, trying config file first, then using defaults
-    pub fn load(config_path: Option<&str>) -> Self {
+    pub fn load(config_path: Option<&str>, registry: &Registry) -> Self {
         let default_path = "config.toml";
         let path = config_path.unwrap_or(default_path);
 
         if Path::new(path).exists() {
-            match Config::from_file(path) {
+            match Config::from_file(path, registry) {
                 Ok(config) => config,
                 Err(e) => {
                     eprintln!("Warning: Failed to load config file: {}. Using defaults.", e);
@@ -209,16 +542,32 @@ impl Config {
     }
 
     /// Apply CLI overrides to the configuration
+    #[allow(clippy::too_many_arguments)]
     pub fn with_cli_overrides(
         mut self,
+        registry: &Registry,
         width: Option<u32>,
         height: Option<u32>,
         algorithm: Option<&str>,
+        params: &[(String, String)],
         complexity: Option<f64>,
+        bias: Option<f64>,
         output: Option<&str>,
         seed: Option<u64>,
         line_color: Option<&str>,
         line_thickness: Option<f32>,
+        openings: Option<Openings>,
+        start: Option<EndpointSpec>,
+        goal: Option<EndpointSpec>,
+        mask: Option<&str>,
+        density: Option<&str>,
+        grid: Option<GridKind>,
+        polar_entrance: Option<PolarEntrance>,
+        levels: Option<u32>,
+        wrap: Option<Wrap>,
+        tiles: Option<u32>,
+        events: Option<&str>,
+        post: &[String],
     ) -> Self {
         if let Some(w) = width {
             self.width = w;
@@ -226,13 +575,23 @@ impl Config {
         if let Some(h) = height {
             self.height = h;
         }
-        if let Some(alg) = algorithm {
-            if let Some(alg_enum) = Algorithm::from_str(alg) {
-                self.algorithm = alg_enum;
+        if let Some(generator) = algorithm.and_then(|alg| registry.get(alg)) {
+            if generator.name() != self.algorithm {
+                // Parameters from the config file belong to the algorithm it chose
+                self.params = Params::new();
             }
+            self.algorithm = generator.name().to_string();
+            // An algorithm on the CLI replaces a composite layout from the config file
+            self.composite = None;
+        }
+        for (key, value) in params {
+            self.params.set(key, value);
         }
         if let Some(c) = complexity {
-            self.complexity = c.max(0.0).min(1.0);
+            self.complexity = c.clamp(0.0, 1.0);
+        }
+        if let Some(b) = bias {
+            self.bias = b.clamp(-1.0, 1.0);
         }
         if let Some(o) = output {
             self.output = o.to_string();
@@ -246,10 +605,51 @@ impl Config {
             }
         }
         if let Some(lt) = line_thickness {
-            if lt >= 0.0 && lt <= 1.0 {
+            if (0.0..=1.0).contains(&lt) {
                 self.solution_line_thickness = lt;
             }
         }
+        if let Some(preset) = openings {
+            self.openings = preset;
+            // A preset on the CLI replaces any endpoints from the config file
+            self.start = None;
+            self.goal = None;
+        }
+        if start.is_some() {
+            self.start = start;
+        }
+        if goal.is_some() {
+            self.goal = goal;
+        }
+        if let Some(m) = mask {
+            self.mask = Some(m.to_string());
+        }
+        if let Some(d) = density {
+            self.density = Some(d.to_string());
+        }
+        if let Some(kind) = grid {
+            self.grid = kind;
+        }
+        if let Some(entrance) = polar_entrance {
+            self.polar_entrance = entrance;
+        }
+        if let Some(l) = levels {
+            self.levels = l.max(1);
+        }
+        if let Some(w) = wrap {
+            self.wrap = w;
+        }
+        if let Some(t) = tiles {
+            self.tiles = t.max(1);
+        }
+        if let Some(e) = events {
+            self.events = Some(e.to_string());
+        }
+        if !post.is_empty() {
+            // Steps on the CLI replace the config file's pipeline; they are
+            // checked when the pipeline is built
+            self.post = post.to_vec();
+        }
         self
     }
 }
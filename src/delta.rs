@@ -0,0 +1,230 @@
+use crate::bitset::BitSet;
+use crate::grid::{Axis, CellId, Grid, Point, Tiling};
+
+/// Passage bits stored per cell
+const EAST: usize = 0;
+const BASE: usize = 1;
+const BITS_PER_CELL: usize = 2;
+
+/// A maze of triangles in rows, alternately pointing up and down
+///
+/// Cell (x, y) points up when x + y is even. Every cell has a west and an
+/// east neighbour in its row, and one neighbour across its horizontal edge:
+/// below for upward triangles, above for downward ones. The entrance is the
+/// west edge of the first cell and the exit the east edge of the last.
+#[derive(Clone, Debug)]
+pub struct DeltaMaze {
+    width: u32,
+    height: u32,
+    passages: BitSet,
+}
+
+impl DeltaMaze {
+    /// Create a new triangle maze with all walls present
+    pub fn new(width: u32, height: u32) -> Self {
+        DeltaMaze {
+            width,
+            height,
+            passages: BitSet::new(width as usize * height as usize * BITS_PER_CELL, false),
+        }
+    }
+
+    /// Get the width of the maze in cells
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get the height of the maze in rows
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Convert (x, y) to a cell id
+    pub fn cell_id(&self, x: u32, y: u32) -> CellId {
+        y as usize * self.width as usize + x as usize
+    }
+
+    /// Convert a cell id back to (x, y)
+    pub fn cell_coords(&self, cell: CellId) -> (u32, u32) {
+        let width = self.width as usize;
+        ((cell % width) as u32, (cell / width) as u32)
+    }
+
+    /// Whether a cell is a triangle pointing up, with its base at the bottom
+    pub fn points_up(&self, cell: CellId) -> bool {
+        let (x, y) = self.cell_coords(cell);
+        (x + y) % 2 == 0
+    }
+
+    /// Neighbour across the horizontal edge, if it is inside the maze
+    fn across(&self, cell: CellId) -> Option<CellId> {
+        let (x, y) = self.cell_coords(cell);
+        if self.points_up(cell) {
+            (y + 1 < self.height).then(|| self.cell_id(x, y + 1))
+        } else {
+            y.checked_sub(1).map(|y| self.cell_id(x, y))
+        }
+    }
+
+    /// Bit holding the passage between two adjacent cells
+    /// Row passages belong to the western cell, vertical ones to the upward cell.
+    fn passage_bit(&self, a: CellId, b: CellId) -> Option<usize> {
+        let (ax, ay) = self.cell_coords(a);
+        let (bx, by) = self.cell_coords(b);
+
+        if ay == by && ax + 1 == bx {
+            Some(a * BITS_PER_CELL + EAST)
+        } else if ay == by && bx + 1 == ax {
+            Some(b * BITS_PER_CELL + EAST)
+        } else if self.across(a) == Some(b) {
+            let upper = if self.points_up(a) { a } else { b };
+            Some(upper * BITS_PER_CELL + BASE)
+        } else {
+            None
+        }
+    }
+
+    /// Corners of a cell: (west, east, apex)
+    fn corners(&self, cell: CellId, cell_size: u32) -> (Point, Point, Point) {
+        let (x, y) = self.cell_coords(cell);
+        let side = cell_size as f64;
+        let row_height = side * 3f64.sqrt() / 2.0;
+        let left = x as f64 * side / 2.0;
+        let top = y as f64 * row_height;
+        let bottom = top + row_height;
+
+        if self.points_up(cell) {
+            ((left, bottom), (left + side, bottom), (left + side / 2.0, top))
+        } else {
+            ((left, top), (left + side, top), (left + side / 2.0, bottom))
+        }
+    }
+}
+
+impl Grid for DeltaMaze {
+    fn cell_count(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    fn neighbors(&self, cell: CellId) -> Vec<CellId> {
+        let (x, y) = self.cell_coords(cell);
+        let mut neighbors = Vec::new();
+
+        if x > 0 {
+            neighbors.push(self.cell_id(x - 1, y));
+        }
+        if x + 1 < self.width {
+            neighbors.push(self.cell_id(x + 1, y));
+        }
+        if let Some(other) = self.across(cell) {
+            neighbors.push(other);
+        }
+
+        neighbors
+    }
+
+    fn link(&mut self, a: CellId, b: CellId) {
+        if let Some(bit) = self.passage_bit(a, b) {
+            self.passages.set(bit, true);
+        }
+    }
+
+    fn unlink(&mut self, a: CellId, b: CellId) {
+        if let Some(bit) = self.passage_bit(a, b) {
+            self.passages.set(bit, false);
+        }
+    }
+
+    fn is_linked(&self, a: CellId, b: CellId) -> bool {
+        self.passage_bit(a, b)
+            .map(|bit| self.passages.get(bit))
+            .unwrap_or(false)
+    }
+
+    fn is_boundary(&self, cell: CellId) -> bool {
+        let (x, _) = self.cell_coords(cell);
+        x == 0 || x + 1 == self.width || self.across(cell).is_none()
+    }
+
+    /// Passages within a row run horizontally; the rest count as vertical
+    fn axis(&self, a: CellId, b: CellId) -> Option<Axis> {
+        if self.cell_coords(a).1 == self.cell_coords(b).1 {
+            Some(Axis::Horizontal)
+        } else {
+            Some(Axis::Vertical)
+        }
+    }
+
+    fn position(&self, cell: CellId) -> Option<(f64, f64)> {
+        let (x, y) = self.cell_coords(cell);
+        Some((
+            (x as f64 + 0.5) / self.width() as f64,
+            (y as f64 + 0.5) / self.height() as f64,
+        ))
+    }
+}
+
+impl Tiling for DeltaMaze {
+    fn name(&self) -> &'static str {
+        "delta"
+    }
+
+    fn start(&self) -> CellId {
+        0
+    }
+
+    fn goal(&self) -> CellId {
+        self.cell_count().saturating_sub(1)
+    }
+
+    /// Triangles are `cell_size` pixels along each side
+    fn canvas_size(&self, cell_size: u32) -> (u32, u32) {
+        let side = cell_size as f64;
+        let width = (self.width as f64 + 1.0) * side / 2.0;
+        let height = self.height as f64 * side * 3f64.sqrt() / 2.0;
+        (width.ceil() as u32 + 1, height.ceil() as u32 + 1)
+    }
+
+    /// Centroid of the triangle
+    fn center(&self, cell: CellId, cell_size: u32) -> Point {
+        let (west, east, apex) = self.corners(cell, cell_size);
+        (
+            (west.0 + east.0 + apex.0) / 3.0,
+            (west.1 + east.1 + apex.1) / 3.0,
+        )
+    }
+
+    /// Each cell draws its east edge, and upward cells their base; west and
+    /// top edges are only drawn on the outer boundary
+    fn walls(&self, cell_size: u32) -> Vec<(Point, Point)> {
+        let mut walls = Vec::new();
+
+        for cell in 0..self.cell_count() {
+            let (x, y) = self.cell_coords(cell);
+            let (west, east, apex) = self.corners(cell, cell_size);
+
+            if x == 0 && cell != self.start() {
+                walls.push((west, apex));
+            }
+
+            let east_open = if x + 1 < self.width {
+                self.is_linked(cell, self.cell_id(x + 1, y))
+            } else {
+                cell == self.goal()
+            };
+            if !east_open {
+                walls.push((apex, east));
+            }
+
+            let base_standing = match self.across(cell) {
+                Some(other) => self.points_up(cell) && !self.is_linked(cell, other),
+                None => true,
+            };
+            if base_standing {
+                walls.push((west, east));
+            }
+        }
+
+        walls
+    }
+}
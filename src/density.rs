@@ -0,0 +1,110 @@
+use crate::field::Field;
+use crate::grid::{CellId, Grid, GridWrapper};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Complexity that varies over the maze, in place of one value for all of it
+///
+/// Written as a field spec such as "image:density.png", where dark areas get
+/// a high complexity and light areas a low one, or given as a function.
+#[derive(Clone)]
+pub enum DensityMap {
+    /// The darkness of a field: 1.0 where the field is 0.0
+    Field(Field),
+    /// Complexity at each point of the unit square
+    Function(Arc<dyn Fn(f64, f64) -> f64 + Send + Sync>),
+}
+
+impl DensityMap {
+    /// Parse a field spec: "image:<path>", "noise[:scale]" or "radial"
+    pub fn parse(s: &str) -> Result<Self, String> {
+        Field::parse(s).map(DensityMap::Field)
+    }
+
+    /// A map that takes the complexity at (x, y) from a function
+    /// Both coordinates run from 0.0 to 1.0; results are held within 0.0 to 1.0.
+    pub fn from_fn(f: impl Fn(f64, f64) -> f64 + Send + Sync + 'static) -> Self {
+        DensityMap::Function(Arc::new(f))
+    }
+
+    /// The map as it would be written; a function has no spec
+    pub fn spec(&self) -> String {
+        match self {
+            DensityMap::Field(field) => field.spec(),
+            DensityMap::Function(_) => "function".to_string(),
+        }
+    }
+
+    /// Complexity at a point of the unit square, from 0.0 to 1.0
+    /// Noise is drawn from `seed`; the other maps ignore it.
+    pub fn complexity(&self, x: f64, y: f64, seed: u64) -> f64 {
+        let complexity = match self {
+            DensityMap::Field(field) => 1.0 - field.sample(x, y, seed),
+            DensityMap::Function(f) => f(x, y),
+        };
+        if complexity.is_nan() {
+            0.0
+        } else {
+            complexity.clamp(0.0, 1.0)
+        }
+    }
+}
+
+impl FromStr for DensityMap {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DensityMap::parse(s)
+    }
+}
+
+impl fmt::Display for DensityMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.spec())
+    }
+}
+
+impl fmt::Debug for DensityMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DensityMap({})", self.spec())
+    }
+}
+
+/// A grid wrapper that gives every cell its own complexity from a density map
+///
+/// Generators that support density read `Grid::complexity` for the cell they
+/// are working on instead of the single complexity they were given, and so
+/// does braiding. Grids without positions keep the single complexity.
+pub struct LocalComplexity<'a> {
+    grid: &'a mut dyn Grid,
+    /// Complexity of every cell, if the grid has positions
+    values: Option<Vec<f64>>,
+}
+
+impl<'a> LocalComplexity<'a> {
+    /// Wrap `grid`, sampling `map` once per cell with noise drawn from `seed`
+    pub fn new(grid: &'a mut dyn Grid, map: &DensityMap, seed: u64) -> Self {
+        let values = (0..grid.cell_count())
+            .map(|cell| grid.position(cell).map(|(x, y)| map.complexity(x, y, seed)))
+            .collect();
+        LocalComplexity { grid, values }
+    }
+}
+
+impl GridWrapper for LocalComplexity<'_> {
+    fn inner(&self) -> &dyn Grid {
+        self.grid
+    }
+
+    fn inner_mut(&mut self) -> &mut dyn Grid {
+        self.grid
+    }
+
+    fn complexity(&self, cell: CellId) -> Option<f64> {
+        match &self.values {
+            Some(values) => Some(values[cell]),
+            None => self.grid.complexity(cell),
+        }
+    }
+}
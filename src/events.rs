@@ -0,0 +1,126 @@
+use crate::grid::{CellId, Grid, GridWrapper};
+use std::fmt;
+
+/// One step of maze generation, in the order it happened
+///
+/// Carves and added walls come from every generator, because they are
+/// recorded where passages change. Visits and backtracks come from the
+/// generators that walk the grid and report them through `Grid::visit` and
+/// `Grid::backtrack`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GenerationEvent {
+    /// A generator reached or walked through a cell
+    Visit(CellId),
+    /// A passage was opened between two adjacent cells
+    Carve(CellId, CellId),
+    /// A wall was put back between two adjacent cells
+    AddWall(CellId, CellId),
+    /// A generator gave up on a cell and stepped back from it
+    Backtrack(CellId),
+}
+
+impl GenerationEvent {
+    /// Write the event with cells shown by `cell`, e.g. "carve (1, 2) -> (2, 2)"
+    pub fn describe(&self, cell: impl Fn(CellId) -> String) -> String {
+        match *self {
+            GenerationEvent::Visit(c) => format!("visit {}", cell(c)),
+            GenerationEvent::Carve(a, b) => format!("carve {} -> {}", cell(a), cell(b)),
+            GenerationEvent::AddWall(a, b) => format!("add_wall {} -> {}", cell(a), cell(b)),
+            GenerationEvent::Backtrack(c) => format!("backtrack {}", cell(c)),
+        }
+    }
+
+    /// The same event with every cell id passed through `cell`, e.g. to move
+    /// the events of a smaller maze to where it lies in a larger one
+    pub fn map_cells(self, cell: impl Fn(CellId) -> CellId) -> Self {
+        match self {
+            GenerationEvent::Visit(c) => GenerationEvent::Visit(cell(c)),
+            GenerationEvent::Carve(a, b) => GenerationEvent::Carve(cell(a), cell(b)),
+            GenerationEvent::AddWall(a, b) => GenerationEvent::AddWall(cell(a), cell(b)),
+            GenerationEvent::Backtrack(c) => GenerationEvent::Backtrack(cell(c)),
+        }
+    }
+
+    /// Play the event back on `grid`: carves and added walls change it, and
+    /// visits and backtracks are reported to it
+    pub fn apply(self, grid: &mut dyn Grid) {
+        match self {
+            GenerationEvent::Visit(c) => grid.visit(c),
+            GenerationEvent::Carve(a, b) => grid.link(a, b),
+            GenerationEvent::AddWall(a, b) => grid.unlink(a, b),
+            GenerationEvent::Backtrack(c) => grid.backtrack(c),
+        }
+    }
+}
+
+impl fmt::Display for GenerationEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe(|cell| cell.to_string()))
+    }
+}
+
+/// A grid wrapper that records the events of carving the grid inside it
+///
+/// Everything is passed through to the wrapped grid, so a generator carves
+/// the same maze with or without a recorder around it.
+pub struct Recorder<'a> {
+    grid: &'a mut dyn Grid,
+    events: Vec<GenerationEvent>,
+}
+
+impl<'a> Recorder<'a> {
+    pub fn new(grid: &'a mut dyn Grid) -> Self {
+        Recorder {
+            grid,
+            events: Vec::new(),
+        }
+    }
+
+    /// Events recorded so far
+    pub fn events(&self) -> &[GenerationEvent] {
+        &self.events
+    }
+
+    /// Stop recording and hand back the events
+    pub fn into_events(self) -> Vec<GenerationEvent> {
+        self.events
+    }
+}
+
+impl GridWrapper for Recorder<'_> {
+    fn inner(&self) -> &dyn Grid {
+        self.grid
+    }
+
+    fn inner_mut(&mut self) -> &mut dyn Grid {
+        self.grid
+    }
+
+    /// Records a carve only when the passage was closed
+    fn link(&mut self, a: CellId, b: CellId) {
+        let was_linked = self.grid.is_linked(a, b);
+        self.grid.link(a, b);
+        if !was_linked && self.grid.is_linked(a, b) {
+            self.events.push(GenerationEvent::Carve(a, b));
+        }
+    }
+
+    /// Records an added wall only when the passage was open
+    fn unlink(&mut self, a: CellId, b: CellId) {
+        let was_linked = self.grid.is_linked(a, b);
+        self.grid.unlink(a, b);
+        if was_linked && !self.grid.is_linked(a, b) {
+            self.events.push(GenerationEvent::AddWall(a, b));
+        }
+    }
+
+    fn visit(&mut self, cell: CellId) {
+        self.grid.visit(cell);
+        self.events.push(GenerationEvent::Visit(cell));
+    }
+
+    fn backtrack(&mut self, cell: CellId) {
+        self.grid.backtrack(cell);
+        self.events.push(GenerationEvent::Backtrack(cell));
+    }
+}
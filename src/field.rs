@@ -0,0 +1,215 @@
+use crate::grid::{CellId, Grid, GridWrapper};
+use image::GrayImage;
+use std::fmt;
+use std::str::FromStr;
+
+/// How strongly a field steers generators when no contrast is given
+pub const DEFAULT_CONTRAST: f64 = 100.0;
+
+/// A value from 0.0 to 1.0 over the unit square, used to weight passages
+///
+/// Written as "noise", "noise:<scale>", "radial" or "image:<path>".
+#[derive(Clone)]
+pub enum Field {
+    /// Smooth Perlin noise with about `scale` blobs across the maze, drawn from the seed
+    Noise { scale: f64 },
+    /// 1.0 in the middle, falling to 0.0 in the corners
+    Radial,
+    /// Brightness of a grayscale image stretched over the maze; white is 1.0
+    Image { path: String, pixels: GrayImage },
+}
+
+impl Field {
+    /// Parse a field spec, loading the image of an "image:<path>" field
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let (name, value) = match s.split_once(':') {
+            Some((name, value)) => (name.trim(), Some(value.trim())),
+            None => (s.trim(), None),
+        };
+
+        match name.to_lowercase().as_str() {
+            "noise" => {
+                let scale = match value {
+                    Some(value) => value
+                        .parse::<f64>()
+                        .map_err(|e| format!("Invalid noise scale: {} ({})", value, e))?,
+                    None => 4.0,
+                };
+                if scale > 0.0 {
+                    Ok(Field::Noise { scale })
+                } else {
+                    Err(format!("Noise scale must be positive, got: {}", scale))
+                }
+            }
+            "radial" => Ok(Field::Radial),
+            "image" => {
+                let path = value.ok_or("Field image needs a path, e.g. image:field.png")?;
+                let pixels = image::open(path)
+                    .map_err(|e| format!("Failed to load field image: {}", e))?
+                    .to_luma8();
+                Ok(Field::Image {
+                    path: path.to_string(),
+                    pixels,
+                })
+            }
+            _ => Err(format!(
+                "Unknown field: {} (use noise[:scale], radial or image:<path>)",
+                s
+            )),
+        }
+    }
+
+    /// The field as it would be written
+    pub fn spec(&self) -> String {
+        match self {
+            Field::Noise { scale } => format!("noise:{}", scale),
+            Field::Radial => "radial".to_string(),
+            Field::Image { path, .. } => format!("image:{}", path),
+        }
+    }
+
+    /// Value of the field at a point of the unit square
+    /// Noise is drawn from `seed`; the other fields ignore it.
+    pub fn sample(&self, x: f64, y: f64, seed: u64) -> f64 {
+        match self {
+            Field::Noise { scale } => fractal_noise(x * scale, y * scale, seed),
+            Field::Radial => {
+                let distance = ((x - 0.5).powi(2) + (y - 0.5).powi(2)).sqrt();
+                (1.0 - distance / 0.5f64.hypot(0.5)).clamp(0.0, 1.0)
+            }
+            Field::Image { pixels, .. } => {
+                let (width, height) = pixels.dimensions();
+                if width == 0 || height == 0 {
+                    return 0.0;
+                }
+                let px = ((x * width as f64) as u32).min(width - 1);
+                let py = ((y * height as f64) as u32).min(height - 1);
+                pixels.get_pixel(px, py)[0] as f64 / 255.0
+            }
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Field::parse(s)
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.spec())
+    }
+}
+
+/// Shown by its spec, leaving out the pixels of an image
+impl fmt::Debug for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Field({})", self.spec())
+    }
+}
+
+/// Pseudo-random value from 0.0 to 1.0 at a lattice point (SplitMix64)
+fn lattice(seed: u64, x: i64, y: i64) -> f64 {
+    let mut h = seed
+        ^ (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    h = (h ^ (h >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    h ^= h >> 31;
+    (h >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Perlin gradient noise, from 0.0 to 1.0
+fn gradient_noise(x: f64, y: f64, seed: u64) -> f64 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (ix, iy) = (x0 as i64, y0 as i64);
+    let fade = |t: f64| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+    let (tx, ty) = (fade(x - x0), fade(y - y0));
+
+    // Dot product of the corner's random unit gradient with the offset to (x, y)
+    let corner = |cx: i64, cy: i64| {
+        let angle = lattice(seed, cx, cy) * std::f64::consts::TAU;
+        angle.cos() * (x - cx as f64) + angle.sin() * (y - cy as f64)
+    };
+    let top = corner(ix, iy) * (1.0 - tx) + corner(ix + 1, iy) * tx;
+    let bottom = corner(ix, iy + 1) * (1.0 - tx) + corner(ix + 1, iy + 1) * tx;
+    let value = top * (1.0 - ty) + bottom * ty;
+
+    // Gradient noise stays within about +-0.71 of zero
+    (0.5 + value / std::f64::consts::SQRT_2).clamp(0.0, 1.0)
+}
+
+/// Three octaves of gradient noise, each twice as fine and half as strong
+/// Each octave is shifted so their lattice lines do not line up.
+fn fractal_noise(x: f64, y: f64, seed: u64) -> f64 {
+    let mut total = 0.0;
+    let mut amplitude = 1.0;
+    let mut frequency = 1.0;
+    let mut norm = 0.0;
+    for octave in 0..3u64 {
+        let shift = octave as f64 * 0.37;
+        total += amplitude
+            * gradient_noise(x * frequency + shift, y * frequency + shift, seed.wrapping_add(octave));
+        norm += amplitude;
+        amplitude /= 2.0;
+        frequency *= 2.0;
+    }
+    total / norm
+}
+
+/// A grid wrapper that weights passages by a field
+///
+/// Each passage takes the field's value at the middle of its two cells, so
+/// generators that follow `Grid::weight` carve first where the field is
+/// high. The weight grows as `exp(contrast * value)`: at 0 the field has no
+/// effect, and the higher the contrast the more closely passages follow it.
+/// Grids without positions are left unweighted.
+pub struct Weighted<'a> {
+    grid: &'a mut dyn Grid,
+    /// Field value of every cell, if the grid has positions
+    values: Option<Vec<f64>>,
+    contrast: f64,
+}
+
+impl<'a> Weighted<'a> {
+    /// Wrap `grid`, sampling `field` once per cell with noise drawn from `seed`
+    /// The contrast is held within +-500, where weights still fit an f64.
+    pub fn new(grid: &'a mut dyn Grid, field: &Field, seed: u64, contrast: f64) -> Self {
+        let values = (0..grid.cell_count())
+            .map(|cell| {
+                grid.position(cell)
+                    .map(|(x, y)| field.sample(x, y, seed))
+            })
+            .collect();
+        Weighted {
+            grid,
+            values,
+            contrast: contrast.clamp(-500.0, 500.0),
+        }
+    }
+}
+
+impl GridWrapper for Weighted<'_> {
+    fn inner(&self) -> &dyn Grid {
+        self.grid
+    }
+
+    fn inner_mut(&mut self) -> &mut dyn Grid {
+        self.grid
+    }
+
+    /// Scales any weight of the wrapped grid by the field along the passage
+    fn weight(&self, a: CellId, b: CellId) -> Option<f64> {
+        let base = self.grid.weight(a, b);
+        match &self.values {
+            Some(values) => {
+                let value = (values[a] + values[b]) / 2.0;
+                Some(base.unwrap_or(1.0) * (self.contrast * value).exp())
+            }
+            None => base,
+        }
+    }
+}
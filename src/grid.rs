@@ -0,0 +1,340 @@
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use std::collections::VecDeque;
+
+/// Identifier of a cell within a grid, from 0 to `cell_count() - 1`
+pub type CellId = usize;
+
+/// A position in image pixels
+pub type Point = (f64, f64);
+
+/// Direction of a passage between two neighbouring cells
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    /// Across the image, or around a ring
+    Horizontal,
+    /// Up and down the image, or in and out of rings
+    Vertical,
+}
+
+/// A maze topology: its cells, which cells are adjacent, and which of those
+/// adjacent pairs are joined by a passage
+///
+/// Tree-based generators only talk to this trait, so the same code carves
+/// square grids and any other tiling that implements it.
+pub trait Grid {
+    /// Number of cell ids, including masked-off cells
+    fn cell_count(&self) -> usize;
+
+    /// Whether a cell is part of the maze
+    fn is_active(&self, _cell: CellId) -> bool {
+        true
+    }
+
+    /// Active cells that share a wall with `cell`
+    fn neighbors(&self, cell: CellId) -> Vec<CellId>;
+
+    /// Open a passage between two adjacent cells
+    fn link(&mut self, a: CellId, b: CellId);
+
+    /// Close the passage between two adjacent cells
+    fn unlink(&mut self, a: CellId, b: CellId);
+
+    /// Whether a passage joins two adjacent cells
+    fn is_linked(&self, a: CellId, b: CellId) -> bool;
+
+    /// Whether `cell` has a wall on the outer boundary of the maze
+    fn is_boundary(&self, cell: CellId) -> bool;
+
+    /// Neighbours reachable through an open passage
+    fn links(&self, cell: CellId) -> Vec<CellId> {
+        self.neighbors(cell)
+            .into_iter()
+            .filter(|&n| self.is_linked(cell, n))
+            .collect()
+    }
+
+    /// Every active cell in id order
+    fn cells(&self) -> Vec<CellId> {
+        (0..self.cell_count()).filter(|&c| self.is_active(c)).collect()
+    }
+
+    /// Number of active cells
+    fn active_count(&self) -> usize {
+        self.cells().len()
+    }
+
+    /// Pick a random active cell
+    fn random_cell(&self, rng: &mut ChaCha8Rng) -> CellId {
+        let cells = self.cells();
+        cells[rng.gen_range(0..cells.len())]
+    }
+
+    /// Which way a passage between two neighbouring cells would run
+    /// None when it runs neither way, like stairs between levels.
+    fn axis(&self, _a: CellId, _b: CellId) -> Option<Axis> {
+        None
+    }
+
+    /// Where a cell lies, scaled so the whole grid fits the unit square
+    /// None for grids without a flat layout; fields that weight passages by
+    /// position leave those grids unweighted.
+    fn position(&self, _cell: CellId) -> Option<(f64, f64)> {
+        None
+    }
+
+    /// How strongly generators that honour weights prefer carving from `a` to `b`
+    /// None means the grid has no preference and generators choose as usual.
+    fn weight(&self, _a: CellId, _b: CellId) -> Option<f64> {
+        None
+    }
+
+    /// Complexity at a cell, where a density map varies it over the maze
+    /// None means generators use the complexity they were given.
+    fn complexity(&self, _cell: CellId) -> Option<f64> {
+        None
+    }
+
+    /// Called by generators when they reach or walk through a cell
+    /// Grids that record generation events note it; others ignore it.
+    fn visit(&mut self, _cell: CellId) {}
+
+    /// Called by generators when they give up on a cell and step back
+    fn backtrack(&mut self, _cell: CellId) {}
+}
+
+/// A grid that wraps another and changes only part of how it behaves
+///
+/// Every `Grid` method is passed through to the wrapped grid, except the
+/// hooks below, which a wrapper overrides to change what generators see
+/// or to watch what they do.
+pub trait GridWrapper {
+    /// The wrapped grid
+    fn inner(&self) -> &dyn Grid;
+
+    /// The wrapped grid, for carving
+    fn inner_mut(&mut self) -> &mut dyn Grid;
+
+    fn link(&mut self, a: CellId, b: CellId) {
+        self.inner_mut().link(a, b);
+    }
+
+    fn unlink(&mut self, a: CellId, b: CellId) {
+        self.inner_mut().unlink(a, b);
+    }
+
+    fn weight(&self, a: CellId, b: CellId) -> Option<f64> {
+        self.inner().weight(a, b)
+    }
+
+    fn complexity(&self, cell: CellId) -> Option<f64> {
+        self.inner().complexity(cell)
+    }
+
+    fn visit(&mut self, cell: CellId) {
+        self.inner_mut().visit(cell);
+    }
+
+    fn backtrack(&mut self, cell: CellId) {
+        self.inner_mut().backtrack(cell);
+    }
+}
+
+impl<W: GridWrapper> Grid for W {
+    fn cell_count(&self) -> usize {
+        self.inner().cell_count()
+    }
+
+    fn is_active(&self, cell: CellId) -> bool {
+        self.inner().is_active(cell)
+    }
+
+    fn neighbors(&self, cell: CellId) -> Vec<CellId> {
+        self.inner().neighbors(cell)
+    }
+
+    fn link(&mut self, a: CellId, b: CellId) {
+        GridWrapper::link(self, a, b);
+    }
+
+    fn unlink(&mut self, a: CellId, b: CellId) {
+        GridWrapper::unlink(self, a, b);
+    }
+
+    fn is_linked(&self, a: CellId, b: CellId) -> bool {
+        self.inner().is_linked(a, b)
+    }
+
+    fn is_boundary(&self, cell: CellId) -> bool {
+        self.inner().is_boundary(cell)
+    }
+
+    fn links(&self, cell: CellId) -> Vec<CellId> {
+        self.inner().links(cell)
+    }
+
+    fn cells(&self) -> Vec<CellId> {
+        self.inner().cells()
+    }
+
+    fn active_count(&self) -> usize {
+        self.inner().active_count()
+    }
+
+    fn random_cell(&self, rng: &mut ChaCha8Rng) -> CellId {
+        self.inner().random_cell(rng)
+    }
+
+    fn axis(&self, a: CellId, b: CellId) -> Option<Axis> {
+        self.inner().axis(a, b)
+    }
+
+    fn position(&self, cell: CellId) -> Option<(f64, f64)> {
+        self.inner().position(cell)
+    }
+
+    fn weight(&self, a: CellId, b: CellId) -> Option<f64> {
+        GridWrapper::weight(self, a, b)
+    }
+
+    fn complexity(&self, cell: CellId) -> Option<f64> {
+        GridWrapper::complexity(self, cell)
+    }
+
+    fn visit(&mut self, cell: CellId) {
+        GridWrapper::visit(self, cell);
+    }
+
+    fn backtrack(&mut self, cell: CellId) {
+        GridWrapper::backtrack(self, cell);
+    }
+}
+
+/// A grid that knows its entrance, exit and how it is drawn
+///
+/// Implemented by the non-square tilings so the CLI and the renderer can
+/// handle them without knowing their shape.
+pub trait Tiling: Grid {
+    /// Name used in settings output, e.g. "hex"
+    fn name(&self) -> &'static str;
+
+    /// Cell with the entrance opening
+    fn start(&self) -> CellId;
+
+    /// Cell with the exit opening
+    fn goal(&self) -> CellId;
+
+    /// Image size in pixels for a given cell size
+    fn canvas_size(&self, cell_size: u32) -> (u32, u32);
+
+    /// Centre of a cell in pixels
+    fn center(&self, cell: CellId, cell_size: u32) -> Point;
+
+    /// Every wall still standing, as line segments in pixels
+    /// The entrance and exit openings are left out.
+    fn walls(&self, cell_size: u32) -> Vec<(Point, Point)>;
+
+    /// Extra line segments drawn with the walls, such as stair markers
+    fn markers(&self, _cell_size: u32) -> Vec<(Point, Point)> {
+        Vec::new()
+    }
+
+    /// Lines to draw a path of cells along, by default one line through their centres
+    /// A path that jumps between separately drawn parts is split into several lines.
+    fn path(&self, cells: &[CellId], cell_size: u32) -> Vec<Vec<Point>> {
+        vec![cells
+            .iter()
+            .map(|&cell| self.center(cell, cell_size))
+            .collect()]
+    }
+
+    /// Find the path from the entrance to the exit
+    fn solve(&self) -> Option<Vec<CellId>> {
+        solve(self, self.start(), self.goal())
+    }
+}
+
+/// Find the shortest path between two cells through open passages using BFS
+pub fn solve<G: Grid + ?Sized>(grid: &G, start: CellId, goal: CellId) -> Option<Vec<CellId>> {
+    let mut parent = vec![None; grid.cell_count()];
+    let mut visited = vec![false; grid.cell_count()];
+    let mut queue = VecDeque::new();
+    visited[start] = true;
+    queue.push_back(start);
+
+    while let Some(current) = queue.pop_front() {
+        if current == goal {
+            // Reconstruct path
+            let mut path = vec![goal];
+            let mut node = goal;
+            while let Some(prev) = parent[node] {
+                path.push(prev);
+                node = prev;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        for next in grid.links(current) {
+            if !visited[next] {
+                visited[next] = true;
+                parent[next] = Some(current);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None // No path found
+}
+
+/// Open walls until every active cell is reachable from every other
+///
+/// Cells are joined to the first neighbouring region found in id order, so
+/// a grid that is already connected is left untouched. A link the grid
+/// refuses, like a side of a weave crossing, leaves the regions apart.
+pub fn ensure_connectivity(grid: &mut dyn Grid) {
+    let cells = grid.cells();
+    connect_cells(grid, &cells);
+}
+
+/// Open walls until every cell in `cells` is reachable from every other,
+/// as `ensure_connectivity` does, opening walls only between those cells
+/// Other cells are left as they are, so cells a generator walled off on
+/// purpose stay that way.
+pub fn connect_cells(grid: &mut dyn Grid, cells: &[CellId]) {
+    fn find(parent: &mut [CellId], mut cell: CellId) -> CellId {
+        while parent[cell] != cell {
+            parent[cell] = parent[parent[cell]];
+            cell = parent[cell];
+        }
+        cell
+    }
+
+    let mut parent: Vec<CellId> = (0..grid.cell_count()).collect();
+    let mut included = vec![false; grid.cell_count()];
+    for &cell in cells {
+        included[cell] = true;
+    }
+
+    for &cell in cells {
+        for next in grid.links(cell) {
+            let (a, b) = (find(&mut parent, cell), find(&mut parent, next));
+            parent[a] = b;
+        }
+    }
+
+    for &cell in cells {
+        for next in grid.neighbors(cell) {
+            if !included[next] {
+                continue;
+            }
+            let (a, b) = (find(&mut parent, cell), find(&mut parent, next));
+            if a != b {
+                grid.link(cell, next);
+                if grid.is_linked(cell, next) {
+                    parent[a] = b;
+                }
+            }
+        }
+    }
+}
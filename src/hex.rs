@@ -0,0 +1,212 @@
+use crate::bitset::BitSet;
+use crate::grid::{Axis, CellId, Grid, Point, Tiling};
+
+/// Directions to the six neighbours of a hex cell, clockwise from east
+/// The first three are stored on the cell; the others belong to the neighbour.
+const EAST: usize = 0;
+const SOUTH_EAST: usize = 1;
+const SOUTH_WEST: usize = 2;
+const NORTH_WEST: usize = 4;
+const DIRECTIONS: usize = 6;
+const BITS_PER_CELL: usize = 3;
+
+/// A maze of pointy-topped hexagons in rows, with odd rows shifted half a cell right
+///
+/// Each cell has up to six neighbours. Passages are stored once per pair of
+/// cells, as east, south-east and south-west bits on the western/northern cell.
+/// The entrance is the north-west wall of the top-left cell and the exit the
+/// south-east wall of the bottom-right cell.
+#[derive(Clone, Debug)]
+pub struct HexMaze {
+    width: u32,
+    height: u32,
+    passages: BitSet,
+}
+
+impl HexMaze {
+    /// Create a new hex maze with all walls present
+    pub fn new(width: u32, height: u32) -> Self {
+        HexMaze {
+            width,
+            height,
+            passages: BitSet::new(width as usize * height as usize * BITS_PER_CELL, false),
+        }
+    }
+
+    /// Get the width of the maze in cells
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get the height of the maze in rows
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Convert (x, y) to a cell id
+    pub fn cell_id(&self, x: u32, y: u32) -> CellId {
+        y as usize * self.width as usize + x as usize
+    }
+
+    /// Convert a cell id back to (x, y)
+    pub fn cell_coords(&self, cell: CellId) -> (u32, u32) {
+        let width = self.width as usize;
+        ((cell % width) as u32, (cell / width) as u32)
+    }
+
+    /// Neighbour of a cell in one of the six directions, if it is inside the maze
+    fn step(&self, cell: CellId, direction: usize) -> Option<CellId> {
+        let (x, y) = self.cell_coords(cell);
+        let (x, y) = (x as i64, y as i64);
+        let shift = y & 1;
+
+        let (nx, ny) = match direction {
+            0 => (x + 1, y),
+            1 => (x + shift, y + 1),
+            2 => (x + shift - 1, y + 1),
+            3 => (x - 1, y),
+            4 => (x + shift - 1, y - 1),
+            _ => (x + shift, y - 1),
+        };
+
+        if nx < 0 || ny < 0 || nx >= self.width as i64 || ny >= self.height as i64 {
+            None
+        } else {
+            Some(self.cell_id(nx as u32, ny as u32))
+        }
+    }
+
+    /// Bit holding the passage between two adjacent cells
+    fn passage_bit(&self, a: CellId, b: CellId) -> Option<usize> {
+        let direction = (0..DIRECTIONS).find(|&d| self.step(a, d) == Some(b))?;
+        if direction < BITS_PER_CELL {
+            Some(a * BITS_PER_CELL + direction)
+        } else {
+            Some(b * BITS_PER_CELL + direction - BITS_PER_CELL)
+        }
+    }
+
+    /// Whether the wall in a direction is one of the two openings
+    fn is_opening(&self, cell: CellId, direction: usize) -> bool {
+        (cell == self.start() && direction == NORTH_WEST)
+            || (cell == self.goal() && direction == SOUTH_EAST)
+    }
+
+    /// Corner of a hexagon, counted clockwise from the upper-right corner
+    fn corner(&self, center: Point, radius: f64, index: usize) -> Point {
+        let angle = (60.0 * index as f64 - 30.0).to_radians();
+        (center.0 + radius * angle.cos(), center.1 + radius * angle.sin())
+    }
+}
+
+impl Grid for HexMaze {
+    fn cell_count(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    fn neighbors(&self, cell: CellId) -> Vec<CellId> {
+        (0..DIRECTIONS)
+            .filter_map(|direction| self.step(cell, direction))
+            .collect()
+    }
+
+    fn link(&mut self, a: CellId, b: CellId) {
+        if let Some(bit) = self.passage_bit(a, b) {
+            self.passages.set(bit, true);
+        }
+    }
+
+    fn unlink(&mut self, a: CellId, b: CellId) {
+        if let Some(bit) = self.passage_bit(a, b) {
+            self.passages.set(bit, false);
+        }
+    }
+
+    fn is_linked(&self, a: CellId, b: CellId) -> bool {
+        self.passage_bit(a, b)
+            .map(|bit| self.passages.get(bit))
+            .unwrap_or(false)
+    }
+
+    fn is_boundary(&self, cell: CellId) -> bool {
+        (0..DIRECTIONS).any(|direction| self.step(cell, direction).is_none())
+    }
+
+    /// Passages within a row run horizontally; the rest count as vertical
+    fn axis(&self, a: CellId, b: CellId) -> Option<Axis> {
+        if self.cell_coords(a).1 == self.cell_coords(b).1 {
+            Some(Axis::Horizontal)
+        } else {
+            Some(Axis::Vertical)
+        }
+    }
+
+    fn position(&self, cell: CellId) -> Option<(f64, f64)> {
+        let (x, y) = self.cell_coords(cell);
+        Some((
+            (x as f64 + 0.5) / self.width() as f64,
+            (y as f64 + 0.5) / self.height() as f64,
+        ))
+    }
+}
+
+impl Tiling for HexMaze {
+    fn name(&self) -> &'static str {
+        "hex"
+    }
+
+    fn start(&self) -> CellId {
+        0
+    }
+
+    fn goal(&self) -> CellId {
+        self.cell_count().saturating_sub(1)
+    }
+
+    /// Cells are `cell_size` pixels across the flat sides
+    fn canvas_size(&self, cell_size: u32) -> (u32, u32) {
+        let cell_width = cell_size as f64;
+        let radius = cell_width / 3f64.sqrt();
+        let width = cell_width * (self.width as f64 + 0.5);
+        let height = radius * (1.5 * self.height as f64 + 0.5);
+        (width.ceil() as u32 + 1, height.ceil() as u32 + 1)
+    }
+
+    fn center(&self, cell: CellId, cell_size: u32) -> Point {
+        let (x, y) = self.cell_coords(cell);
+        let cell_width = cell_size as f64;
+        let radius = cell_width / 3f64.sqrt();
+        let shift = if y % 2 == 1 { 0.5 } else { 0.0 };
+        (
+            cell_width * (x as f64 + 0.5 + shift),
+            radius * (1.0 + 1.5 * y as f64),
+        )
+    }
+
+    /// Each cell draws its east, south-east and south-west walls, plus any
+    /// other wall on the outer boundary
+    fn walls(&self, cell_size: u32) -> Vec<(Point, Point)> {
+        let radius = cell_size as f64 / 3f64.sqrt();
+        let mut walls = Vec::new();
+
+        for cell in 0..self.cell_count() {
+            let center = self.center(cell, cell_size);
+            for direction in EAST..DIRECTIONS {
+                let standing = match self.step(cell, direction) {
+                    Some(neighbor) => {
+                        direction <= SOUTH_WEST && !self.is_linked(cell, neighbor)
+                    }
+                    None => !self.is_opening(cell, direction),
+                };
+                if standing {
+                    walls.push((
+                        self.corner(center, radius, direction),
+                        self.corner(center, radius, direction + 1),
+                    ));
+                }
+            }
+        }
+
+        walls
+    }
+}
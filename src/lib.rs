@@ -1,6 +1,16 @@
 pub mod algorithms;
+pub mod bias;
+mod bitset;
+pub mod delta;
+pub mod density;
+pub mod events;
+pub mod field;
+pub mod grid;
+pub mod hex;
+pub mod mask;
 pub mod maze;
+pub mod maze3d;
+pub mod polar;
+pub mod postprocess;
 pub mod render;
-
-
-
+pub mod weave;
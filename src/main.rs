@@ -1,11 +1,46 @@
 pub mod algorithms;
+pub mod bias;
+mod bitset;
 mod config;
+pub mod delta;
+pub mod density;
+pub mod events;
+pub mod field;
+pub mod grid;
+pub mod hex;
+pub mod mask;
 pub mod maze;
+pub mod maze3d;
+pub mod polar;
+pub mod postprocess;
 pub mod render;
+pub mod weave;
 
-use clap::Parser;
-use config::{Algorithm, Config};
-use render::{save_maze, save_maze_with_solution};
+use clap::{CommandFactory, FromArgMatches, Parser};
+use algorithms::composite::{Composite, Region};
+use algorithms::{GenerateError, MazeGenerator, Params, Registry};
+use bias::{describe_bias, parse_bias, Biased};
+use config::{
+    parse_grid, parse_openings, parse_polar_entrance, parse_wrap, CompositeConfig, Config,
+    EndpointSpec, GridKind, Openings,
+};
+use delta::DeltaMaze;
+use density::{DensityMap, LocalComplexity};
+use events::{GenerationEvent, Recorder};
+use grid::{CellId, Grid, Tiling};
+use hex::HexMaze;
+use mask::Mask;
+use maze::{Endpoint, Maze, Wrap};
+use maze3d::Maze3d;
+use polar::{PolarEntrance, PolarMaze};
+use postprocess::Pipeline;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use render::{
+    save_maze, save_maze_tiled, save_maze_with_solution, save_maze_with_solution_tiled,
+    save_tiling, save_tiling_with_solution,
+};
+use weave::WeaveMaze;
 
 #[derive(Parser, Debug)]
 #[command(name = "maze_generator")]
@@ -19,14 +54,26 @@ struct Args {
     #[arg(long)]
     height: Option<u32>,
 
-    /// Algorithm to use: recursive_backtracking, kruskal, prim, aldous_broder, wilsons, recursive_division, growing_tree, hunt_and_kill, binary_tree, sidewinder, eller, dfs_iterative, bfs, recursive_backtracking_braided, cellular_automata, drunkards_walk, random_obstacle, hamiltonian, voronoi
-    #[arg(long)]
+    /// Algorithm to use (the help lists every registered generator)
+    #[arg(long, value_parser = parse_algorithm)]
     algorithm: Option<String>,
 
+    /// Algorithm parameter as key=value; repeat for several (see --list-algorithms)
+    #[arg(long = "param", value_name = "KEY=VALUE", value_parser = Params::parse_assignment)]
+    params: Vec<(String, String)>,
+
+    /// List the available algorithms with their options, then exit
+    #[arg(long)]
+    list_algorithms: bool,
+
     /// Complexity parameter (0.0 to 1.0)
     #[arg(long)]
     complexity: Option<f64>,
 
+    /// Directional bias: -1.0 (vertical shafts) to 1.0 (horizontal runs), or horizontal[:strength] / vertical[:strength]
+    #[arg(long, value_parser = parse_bias, allow_hyphen_values = true)]
+    bias: Option<f64>,
+
     /// Output file path
     #[arg(long)]
     output: Option<String>,
@@ -46,67 +93,626 @@ struct Args {
     /// Solution line thickness (overrides auto-calculation)
     #[arg(long)]
     line_thickness: Option<f32>,
+
+    /// Entrance/exit layout: corners, left-right, top-bottom
+    #[arg(long, value_parser = parse_openings)]
+    openings: Option<Openings>,
+
+    /// Entrance cell as "x,y" or "x,y,side" (side: north, south, east, west)
+    #[arg(long, value_parser = EndpointSpec::parse)]
+    start: Option<EndpointSpec>,
+
+    /// Exit cell as "x,y" or "x,y,side" (side: north, south, east, west)
+    #[arg(long, value_parser = EndpointSpec::parse)]
+    goal: Option<EndpointSpec>,
+
+    /// Shape mask: a black-and-white PNG or an ASCII file ('.' and ' ' are outside)
+    #[arg(long)]
+    mask: Option<String>,
+
+    /// Density map giving each region its own complexity (dark is complex): image:<path>, noise[:scale], radial
+    #[arg(long)]
+    density: Option<String>,
+
+    /// Cell shape: square, hex, delta, polar, weave (polar uses --height as the ring count)
+    #[arg(long, value_parser = parse_grid)]
+    grid: Option<GridKind>,
+
+    /// Where a polar maze is entered: center, rim
+    #[arg(long, value_parser = parse_polar_entrance)]
+    polar_entrance: Option<PolarEntrance>,
+
+    /// Number of levels joined by stairs (square grid only)
+    #[arg(long)]
+    levels: Option<u32>,
+
+    /// Join the maze edges: none, cylinder (left-right), torus (both)
+    #[arg(long, value_parser = parse_wrap)]
+    wrap: Option<Wrap>,
+
+    /// Render this many copies along each wrapped axis
+    #[arg(long)]
+    tiles: Option<u32>,
+
+    /// Write every generation step (visit, carve, backtrack, add_wall) to this file
+    #[arg(long)]
+    events: Option<String>,
+
+    /// Post-processing step run after generation; repeat to chain: braid[:share], cull[:share], sparsify:share, add-loops:count
+    #[arg(long)]
+    post: Vec<String>,
+}
+
+/// Canonical name of a generator in the built-in registry, which the CLI runs
+fn parse_algorithm(name: &str) -> Result<String, String> {
+    let registry = Registry::builtin();
+    registry
+        .get(name)
+        .map(|generator| generator.name().to_string())
+        .ok_or_else(|| format!("Unknown algorithm: {} (known: {})", name, registry.names().join(", ")))
+}
+
+/// Output path for the solved image, derived from the maze output path
+fn solved_path(output: &str) -> String {
+    if output.ends_with(".png") {
+        output.replace(".png", "_solved.png")
+    } else {
+        format!("{}_solved.png", output)
+    }
+}
+
+/// Generate, render and solve a maze on a non-square grid
+fn generate_tiling<T: Tiling>(
+    config: &Config,
+    generator: &dyn MazeGenerator,
+    pipeline: &Pipeline,
+    density: Option<&DensityMap>,
+    seed: u64,
+    mut maze: T,
+) {
+    println!("Generating maze with:");
+    println!("  Width: {}", config.width);
+    println!("  Height: {}", config.height);
+    println!("  Grid: {}", maze.name());
+    if config.levels > 1 {
+        println!("  Levels: {}", config.levels);
+    }
+    println!("  Algorithm: {}", generator.name());
+    if let Some(composite) = &config.composite {
+        println!("  Regions: {}", describe_composite(composite));
+        println!("  Passages: {} between neighbouring regions", composite.passages);
+    } else if !config.params.is_empty() {
+        println!("  Params: {}", config.params);
+    }
+    println!("  Complexity: {:.2}", config.complexity);
+    if config.bias != 0.0 {
+        println!("  Bias: {}", describe_bias(config.bias));
+    }
+    if let Some(density) = density {
+        println!("  Density: {}", density);
+    }
+    if !pipeline.is_empty() {
+        println!("  Post: {}", pipeline.specs().join(", "));
+    }
+    println!("  Seed: {}", describe_seed(config, seed));
+    println!("  Output: {}", config.output);
+
+    if config.mask.is_some() || config.start.is_some() || config.goal.is_some() {
+        eprintln!(
+            "Error: mask, start and goal are only supported on square grids, not {}",
+            maze.name()
+        );
+        std::process::exit(1);
+    }
+
+    if maze.active_count() == 0 {
+        let e = GenerateError::InvalidDimensions {
+            width: config.width,
+            height: config.height,
+        };
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    // Generate maze
+    println!("Generating maze...");
+    let mut rng = algorithms::seeded_rng(Some(seed));
+    let keep = [maze.start(), maze.goal()];
+    let mut carve = |grid: &mut dyn Grid| {
+        carve_configured(config, generator, pipeline, density, grid, &keep, &mut rng)
+    };
+    let carved = match &config.events {
+        Some(path) => {
+            let mut recorder = Recorder::new(&mut maze);
+            let carved = carve(&mut recorder);
+            if carved.is_ok() {
+                save_events(path, recorder.events(), |cell| cell.to_string());
+            }
+            carved
+        }
+        None => carve(&mut maze),
+    };
+    if let Err(e) = carved {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    // Save to PNG
+    println!("Rendering to PNG...");
+    if let Err(e) = save_tiling(&maze, config.cell_size, &config.output) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    println!("Maze saved to {}", config.output);
+
+    // Always solve maze and save solution
+    println!("Solving maze...");
+    let Some(solution) = maze.solve() else {
+        eprintln!("Error: Could not solve maze (no path found)");
+        std::process::exit(1);
+    };
+    println!("Solution found with {} steps", solution.len());
+    let solved_path = solved_path(&config.output);
+    match save_tiling_with_solution(
+        &maze,
+        config.cell_size,
+        &solution,
+        &solved_path,
+        &config.solution_line_color,
+        config.solution_line_thickness,
+    ) {
+        Ok(()) => println!("Solved maze saved to {}", solved_path),
+        Err(e) => {
+            eprintln!("Error saving solved maze: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Carve a grid with the configured bias and density map, then post-process it
+fn carve_configured(
+    config: &Config,
+    generator: &dyn MazeGenerator,
+    pipeline: &Pipeline,
+    density: Option<&DensityMap>,
+    grid: &mut dyn Grid,
+    keep: &[CellId],
+    rng: &mut ChaCha8Rng,
+) -> Result<(), GenerateError> {
+    if let Some(map) = density {
+        // Every cell gets its own complexity, which carve_grid and braiding read
+        let seed = rng.gen();
+        let mut local = LocalComplexity::new(grid, map, seed);
+        return carve_configured(config, generator, pipeline, None, &mut local, keep, rng);
+    }
+    if config.bias != 0.0 {
+        generator.carve_grid(&mut Biased::new(grid, config.bias), config.complexity, rng)?;
+    } else {
+        generator.carve_grid(grid, config.complexity, rng)?;
+    }
+    pipeline.apply(grid, keep, rng);
+    Ok(())
+}
+
+/// Generators for the regions of a composite layout, with their params applied
+/// Regions without params get None and use the registry's generator as it is.
+fn configure_regions(
+    registry: &Registry,
+    composite: &CompositeConfig,
+) -> Result<Vec<Option<Box<dyn MazeGenerator>>>, String> {
+    composite
+        .regions
+        .iter()
+        .map(|region| {
+            let generator = registry
+                .get(&region.algorithm)
+                .ok_or_else(|| format!("Unknown algorithm: {}", region.algorithm))?;
+            if region.params.is_empty() {
+                Ok(None)
+            } else {
+                generator.with_params(&region.params).map(Some)
+            }
+        })
+        .collect()
+}
+
+/// Build the composite generator, loading each zone mask at the maze's size
+fn build_composite<'a>(
+    registry: &'a Registry,
+    composite: &CompositeConfig,
+    configured: &'a [Option<Box<dyn MazeGenerator>>],
+    width: u32,
+    height: u32,
+) -> Result<Composite<'a>, String> {
+    let mut regions = Vec::new();
+    for (region, configured) in composite.regions.iter().zip(configured) {
+        let generator = match configured {
+            Some(generator) => generator.as_ref(),
+            None => registry
+                .get(&region.algorithm)
+                .ok_or_else(|| format!("Unknown algorithm: {}", region.algorithm))?,
+        };
+        let zone = match &region.zone {
+            Some(path) => Some(Mask::from_file(path, width, height)?),
+            None => None,
+        };
+        regions.push(Region {
+            generator,
+            complexity: region.complexity,
+            zone,
+        });
+    }
+    let mut generator = Composite::new(composite.layout, regions);
+    generator.passages = composite.passages;
+    Ok(generator)
+}
+
+/// Describe a composite layout for the summary, e.g. "bands of binary_tree, wilsons"
+fn describe_composite(composite: &CompositeConfig) -> String {
+    let regions: Vec<String> = composite
+        .regions
+        .iter()
+        .map(|region| {
+            let mut parts = vec![region.algorithm.clone()];
+            if !region.params.is_empty() {
+                parts.push(region.params.to_string());
+            }
+            if let Some(complexity) = region.complexity {
+                parts.push(format!("complexity {:.2}", complexity));
+            }
+            if let Some(zone) = &region.zone {
+                parts.push(format!("zone {}", zone));
+            }
+            match parts.len() {
+                1 => parts.remove(0),
+                _ => format!("{} ({})", parts[0], parts[1..].join(", ")),
+            }
+        })
+        .collect();
+    format!("{} of {}", composite.layout, regions.join(", "))
+}
+
+/// Print every registered generator with its aliases, description and options
+fn list_algorithms(registry: &Registry) {
+    for generator in registry.iter() {
+        println!("{}", generator.name());
+        println!("    {}", generator.description());
+        if !generator.aliases().is_empty() {
+            println!("    Aliases: {}", generator.aliases().join(", "));
+        }
+        let mut supports = Vec::new();
+        if generator.supports_mask() {
+            supports.push("masks");
+        }
+        if generator.supports_grids() {
+            supports.push("all grid shapes");
+        }
+        if generator.supports_weights() {
+            supports.push("bias");
+        }
+        if generator.supports_density() {
+            supports.push("density");
+        }
+        if !supports.is_empty() {
+            println!("    Supports: {}", supports.join(", "));
+        }
+        for option in generator.options() {
+            println!("    {}: {}", option.name, option.description);
+        }
+    }
+}
+
+/// Write generation events to a file, one per line, with cells shown by `cell`
+fn save_events(path: &str, events: &[GenerationEvent], cell: impl Fn(CellId) -> String) {
+    let lines: String = events
+        .iter()
+        .map(|event| event.describe(&cell) + "\n")
+        .collect();
+    match std::fs::write(path, lines) {
+        Ok(()) => println!("{} generation events saved to {}", events.len(), path),
+        Err(e) => {
+            eprintln!("Error: Failed to write events to {}: {}", path, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Format the seed for the settings summary, noting when it was drawn at random
+fn describe_seed(config: &Config, seed: u64) -> String {
+    if config.seed.is_some() {
+        seed.to_string()
+    } else {
+        format!("{} (random)", seed)
+    }
+}
+
+/// Format an endpoint for the settings summary
+fn describe_endpoint(endpoint: &Endpoint) -> String {
+    match endpoint.side {
+        Some(side) => format!("({}, {}) {}", endpoint.x, endpoint.y, side.name()),
+        None => format!("({}, {})", endpoint.x, endpoint.y),
+    }
 }
 
 fn main() {
-    let args = Args::parse();
+    let registry = Registry::builtin();
+    let algorithm_help = format!("Algorithm to use: {}", registry.names().join(", "));
+    let matches = Args::command()
+        .mut_arg("algorithm", |arg| arg.help(algorithm_help))
+        .get_matches();
+    let args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+    if args.list_algorithms {
+        list_algorithms(&registry);
+        return;
+    }
 
     // Load configuration
-    let config = Config::load(args.config.as_deref()).with_cli_overrides(
+    let config = Config::load(args.config.as_deref(), &registry).with_cli_overrides(
+        &registry,
         args.width,
         args.height,
         args.algorithm.as_deref(),
+        &args.params,
         args.complexity,
+        args.bias,
         args.output.as_deref(),
         args.seed,
         args.line_color.as_deref(),
         args.line_thickness,
+        args.openings,
+        args.start,
+        args.goal,
+        args.mask.as_deref(),
+        args.density.as_deref(),
+        args.grid,
+        args.polar_entrance,
+        args.levels,
+        args.wrap,
+        args.tiles,
+        args.events.as_deref(),
+        &args.post,
     );
 
+    // Select algorithm
+    let Some(generator) = registry.get(&config.algorithm) else {
+        eprintln!("Error: Unknown algorithm: {}", config.algorithm);
+        std::process::exit(1);
+    };
+    let configured;
+    let generator = if config.params.is_empty() {
+        generator
+    } else {
+        match generator.with_params(&config.params) {
+            Ok(generator) => {
+                configured = generator;
+                configured.as_ref()
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    };
+
+    // A composite layout takes the place of the algorithm, carving each of
+    // its regions with a generator of its own
+    let configured_regions = match &config.composite {
+        Some(composite) => match configure_regions(&registry, composite) {
+            Ok(configured) => configured,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        None => Vec::new(),
+    };
+    let composite = config.composite.as_ref().map(|composite| {
+        build_composite(
+            &registry,
+            composite,
+            &configured_regions,
+            config.width,
+            config.height,
+        )
+        .unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        })
+    });
+    let generator = match &composite {
+        Some(composite) => composite,
+        None => generator,
+    };
+
+    let pipeline = match Pipeline::parse(&config.post) {
+        Ok(pipeline) => pipeline,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Draw the seed now so it can be shown, and passed back with --seed
+    let seed = algorithms::resolve_seed(config.seed);
+
+    let density = match config.density.as_deref().map(DensityMap::parse).transpose() {
+        Ok(density) => density,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if density.is_some() && !generator.supports_density() {
+        eprintln!(
+            "Error: Algorithm {} does not support a density map",
+            generator.name()
+        );
+        std::process::exit(1);
+    }
+
+    if config.bias != 0.0 && !generator.supports_weights() {
+        eprintln!(
+            "Error: Algorithm {} does not support a directional bias",
+            generator.name()
+        );
+        std::process::exit(1);
+    }
+
+    if config.levels > 1 && config.grid != GridKind::Square {
+        eprintln!("Error: levels are only supported on square grids");
+        std::process::exit(1);
+    }
+    if config.wrap != Wrap::None && (config.grid != GridKind::Square || config.levels > 1) {
+        eprintln!("Error: wrap is only supported on single-level square grids");
+        std::process::exit(1);
+    }
+
+    match config.grid {
+        GridKind::Square if config.levels > 1 => {
+            let maze = Maze3d::new(config.width, config.height, config.levels);
+            generate_tiling(&config, generator, &pipeline, density.as_ref(), seed, maze);
+            return;
+        }
+        GridKind::Square => {}
+        GridKind::Hex => {
+            let maze = HexMaze::new(config.width, config.height);
+            generate_tiling(&config, generator, &pipeline, density.as_ref(), seed, maze);
+            return;
+        }
+        GridKind::Delta => {
+            let maze = DeltaMaze::new(config.width, config.height);
+            generate_tiling(&config, generator, &pipeline, density.as_ref(), seed, maze);
+            return;
+        }
+        GridKind::Polar => {
+            let maze = PolarMaze::new(config.height, config.polar_entrance);
+            generate_tiling(&config, generator, &pipeline, density.as_ref(), seed, maze);
+            return;
+        }
+        GridKind::Weave => {
+            let maze = WeaveMaze::new(config.width, config.height);
+            generate_tiling(&config, generator, &pipeline, density.as_ref(), seed, maze);
+            return;
+        }
+    }
+
+    // Build the blank maze, restricted to the mask if one is configured
+    let mut maze = match &config.mask {
+        Some(path) => match Mask::from_file(path, config.width, config.height) {
+            Ok(mut mask) => {
+                mask.keep_largest_region();
+                Maze::with_mask(mask)
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        None => Maze::new(config.width, config.height),
+    };
+    maze.set_wrap(config.wrap);
+
+    if let Err(e) = generator.check(&maze) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    let (start, goal) = match config.endpoints(&maze) {
+        Ok(endpoints) => endpoints,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    maze.set_start(start);
+    maze.set_goal(goal);
+
     println!("Generating maze with:");
     println!("  Width: {}", config.width);
     println!("  Height: {}", config.height);
-    println!("  Algorithm: {}", config.algorithm.to_string());
+    println!("  Algorithm: {}", generator.name());
+    if let Some(composite) = &config.composite {
+        println!("  Regions: {}", describe_composite(composite));
+        println!("  Passages: {} between neighbouring regions", composite.passages);
+    } else if !config.params.is_empty() {
+        println!("  Params: {}", config.params);
+    }
     println!("  Complexity: {:.2}", config.complexity);
-    if let Some(seed) = config.seed {
-        println!("  Seed: {}", seed);
-    } else {
-        println!("  Seed: random");
+    if config.bias != 0.0 {
+        println!("  Bias: {}", describe_bias(config.bias));
+    }
+    if let Some(density) = &density {
+        println!("  Density: {}", density);
+    }
+    if !pipeline.is_empty() {
+        println!("  Post: {}", pipeline.specs().join(", "));
     }
+    println!("  Seed: {}", describe_seed(&config, seed));
+    if let Some(path) = &config.mask {
+        println!("  Mask: {} ({} active cells)", path, maze.active_count());
+    }
+    if config.wrap != Wrap::None {
+        println!("  Wrap: {}", config.wrap.name());
+    }
+    println!("  Start: {}", describe_endpoint(&start));
+    println!("  Goal: {}", describe_endpoint(&goal));
     println!("  Output: {}", config.output);
 
-    // Select algorithm
-    let generator: Box<dyn algorithms::MazeGenerator> = match config.algorithm {
-        Algorithm::RecursiveBacktracking => {
-            Box::new(algorithms::RecursiveBacktracking)
-        }
-        Algorithm::Kruskal => Box::new(algorithms::Kruskal),
-        Algorithm::Prim => Box::new(algorithms::Prim),
-        Algorithm::AldousBroder => Box::new(algorithms::AldousBroder),
-        Algorithm::Wilsons => Box::new(algorithms::Wilsons),
-        Algorithm::RecursiveDivision => Box::new(algorithms::RecursiveDivision),
-        Algorithm::GrowingTree => Box::new(algorithms::GrowingTree),
-        Algorithm::HuntAndKill => Box::new(algorithms::HuntAndKill),
-        Algorithm::BinaryTree => Box::new(algorithms::BinaryTree),
-        Algorithm::Sidewinder => Box::new(algorithms::Sidewinder),
-        Algorithm::Eller => Box::new(algorithms::Eller),
-        Algorithm::DfsIterative => Box::new(algorithms::DfsIterative),
-        Algorithm::Bfs => Box::new(algorithms::Bfs),
-        Algorithm::RecursiveBacktrackingBraided => Box::new(algorithms::RecursiveBacktrackingBraided),
-        Algorithm::CellularAutomata => Box::new(algorithms::CellularAutomata),
-        Algorithm::DrunkardsWalk => Box::new(algorithms::DrunkardsWalk),
-        Algorithm::RandomObstacle => Box::new(algorithms::RandomObstacle),
-        Algorithm::Hamiltonian => Box::new(algorithms::Hamiltonian),
-        Algorithm::Voronoi => Box::new(algorithms::Voronoi),
-    };
-
     // Generate maze
     println!("Generating maze...");
-    let maze = generator.generate(config.width, config.height, config.complexity, config.seed);
+    let mut rng = algorithms::seeded_rng(Some(seed));
+    if config.events.is_some() {
+        maze.record_events();
+    }
+    let keep = [maze.cell_id(start.x, start.y), maze.cell_id(goal.x, goal.y)];
+    if config.bias != 0.0 || density.is_some() {
+        // The bias and density map reach the generator through the grid, as
+        // passage weights and per-cell complexity that carve_grid follows
+        let carved = carve_configured(
+            &config,
+            generator,
+            &pipeline,
+            density.as_ref(),
+            &mut maze,
+            &keep,
+            &mut rng,
+        );
+        if let Err(e) = carved {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    } else {
+        generator.carve(&mut maze, config.complexity, &mut rng);
+        pipeline.apply(&mut maze, &keep, &mut rng);
+    }
+    if maze.start() != start || maze.goal() != goal {
+        println!(
+            "Openings moved to {} and {}",
+            describe_endpoint(&maze.start()),
+            describe_endpoint(&maze.goal())
+        );
+    }
+    if !maze.rooms().is_empty() {
+        let doors: usize = maze.rooms().iter().map(|room| room.doors.len()).sum();
+        println!("Placed {} rooms with {} doors", maze.rooms().len(), doors);
+    }
+    if let Some(path) = &config.events {
+        let events = maze.take_events();
+        save_events(path, &events, |cell| {
+            let (x, y) = maze.cell_coords(cell);
+            format!("({}, {})", x, y)
+        });
+    }
 
     // Save to PNG
     println!("Rendering to PNG...");
-    match save_maze(&maze, config.cell_size, &config.output) {
+    let saved = if config.tiles > 1 {
+        save_maze_tiled(&maze, config.cell_size, config.tiles, &config.output)
+    } else {
+        save_maze(&maze, config.cell_size, &config.output)
+    };
+    match saved {
         Ok(()) => {
             println!("Maze saved to {}", config.output);
         }
@@ -121,20 +727,28 @@ fn main() {
     match maze.solve() {
         Some(solution) => {
             println!("Solution found with {} steps", solution.len());
-            // Generate solved filename based on output filename
-            let solved_path = if config.output.ends_with(".png") {
-                config.output.replace(".png", "_solved.png")
+            let solved_path = solved_path(&config.output);
+            let saved = if config.tiles > 1 {
+                save_maze_with_solution_tiled(
+                    &maze,
+                    config.cell_size,
+                    config.tiles,
+                    &solution,
+                    &solved_path,
+                    &config.solution_line_color,
+                    config.solution_line_thickness,
+                )
             } else {
-                format!("{}_solved.png", config.output)
+                save_maze_with_solution(
+                    &maze,
+                    config.cell_size,
+                    &solution,
+                    &solved_path,
+                    &config.solution_line_color,
+                    config.solution_line_thickness,
+                )
             };
-            match save_maze_with_solution(
-                &maze,
-                config.cell_size,
-                &solution,
-                &solved_path,
-                &config.solution_line_color,
-                config.solution_line_thickness,
-            ) {
+            match saved {
                 Ok(()) => {
                     println!("Solved maze saved to {}", solved_path);
                 }
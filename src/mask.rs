@@ -0,0 +1,184 @@
+use crate::bitset::BitSet;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+
+/// Marks which cells of a rectangular grid belong to the maze
+///
+/// Cells outside the mask are left out of generation, solving and rendering,
+/// so the maze takes the shape of the silhouette.
+#[derive(Clone, Debug)]
+pub struct Mask {
+    width: u32,
+    height: u32,
+    active: BitSet,
+}
+
+impl Mask {
+    /// Create a mask with every cell active
+    pub fn new(width: u32, height: u32) -> Self {
+        Mask {
+            width,
+            height,
+            active: BitSet::new(width as usize * height as usize, true),
+        }
+    }
+
+    /// Load a mask from a PNG or ASCII file, scaled to `width` x `height` cells
+    /// Files ending in `.png` are read as images; anything else as ASCII text.
+    pub fn from_file<P: AsRef<Path>>(path: P, width: u32, height: u32) -> Result<Self, String> {
+        let path = path.as_ref();
+        let is_png = path
+            .extension()
+            .map(|ext| ext.eq_ignore_ascii_case("png"))
+            .unwrap_or(false);
+
+        let mask = if is_png {
+            Mask::from_png(path)?
+        } else {
+            let content = fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read mask file: {}", e))?;
+            Mask::from_ascii(&content)?
+        };
+
+        Ok(mask.resize(width, height))
+    }
+
+    /// Load a mask from a black-and-white image, one pixel per cell
+    /// Dark opaque pixels are active; light or transparent pixels are masked off.
+    pub fn from_png<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let img = image::open(path.as_ref())
+            .map_err(|e| format!("Failed to load mask image: {}", e))?
+            .to_luma_alpha8();
+
+        let mut mask = Mask::new(img.width(), img.height());
+        for (x, y, pixel) in img.enumerate_pixels() {
+            let [luma, alpha] = pixel.0;
+            mask.set_active(x, y, luma < 128 && alpha >= 128);
+        }
+
+        Ok(mask)
+    }
+
+    /// Parse a mask from text, one character per cell
+    /// `.` and spaces are masked off; any other character is active.
+    /// Short lines are padded with masked-off cells.
+    pub fn from_ascii(text: &str) -> Result<Self, String> {
+        let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        if width == 0 {
+            return Err("Mask text contains no cells".to_string());
+        }
+
+        let mut mask = Mask::new(width as u32, lines.len() as u32);
+        for (y, line) in lines.iter().enumerate() {
+            let mut chars = line.chars();
+            for x in 0..width {
+                let active = matches!(chars.next(), Some(c) if c != '.' && c != ' ');
+                mask.set_active(x as u32, y as u32, active);
+            }
+        }
+
+        Ok(mask)
+    }
+
+    /// Get the width of the mask
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get the height of the mask
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Check whether the cell at (x, y) is part of the maze
+    pub fn is_active(&self, x: u32, y: u32) -> bool {
+        x < self.width && y < self.height && self.active.get(self.index(x, y))
+    }
+
+    /// Include or exclude the cell at (x, y)
+    pub fn set_active(&mut self, x: u32, y: u32, active: bool) {
+        if x < self.width && y < self.height {
+            let index = self.index(x, y);
+            self.active.set(index, active);
+        }
+    }
+
+    /// Count the active cells
+    pub fn active_count(&self) -> usize {
+        self.active.count_ones()
+    }
+
+    /// Scale the mask to `width` x `height` using nearest-neighbour sampling
+    pub fn resize(&self, width: u32, height: u32) -> Mask {
+        if width == self.width && height == self.height {
+            return self.clone();
+        }
+
+        let mut resized = Mask::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let sx = (x as u64 * self.width as u64 / width as u64) as u32;
+                let sy = (y as u64 * self.height as u64 / height as u64) as u32;
+                resized.set_active(x, y, self.is_active(sx, sy));
+            }
+        }
+        resized
+    }
+
+    /// Mask off every cell outside the largest 4-connected region
+    ///
+    /// Cells in separate islands could never be joined to the rest of the
+    /// maze, so they are dropped rather than left unreachable.
+    pub fn keep_largest_region(&mut self) {
+        let mut region = vec![usize::MAX; self.width as usize * self.height as usize];
+        let mut sizes = Vec::new();
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !self.is_active(x, y) || region[self.index(x, y)] != usize::MAX {
+                    continue;
+                }
+
+                // Flood fill a new region
+                let id = sizes.len();
+                let mut size = 0;
+                let mut queue = VecDeque::new();
+                queue.push_back((x, y));
+                region[self.index(x, y)] = id;
+
+                while let Some((cx, cy)) = queue.pop_front() {
+                    size += 1;
+                    let neighbors = [
+                        (cx.wrapping_sub(1), cy),
+                        (cx + 1, cy),
+                        (cx, cy.wrapping_sub(1)),
+                        (cx, cy + 1),
+                    ];
+                    for (nx, ny) in neighbors {
+                        if self.is_active(nx, ny) && region[self.index(nx, ny)] == usize::MAX {
+                            region[self.index(nx, ny)] = id;
+                            queue.push_back((nx, ny));
+                        }
+                    }
+                }
+                sizes.push(size);
+            }
+        }
+
+        let largest = (0..sizes.len()).max_by_key(|&id| sizes[id]);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let id = region[self.index(x, y)];
+                if id != usize::MAX && Some(id) != largest {
+                    self.set_active(x, y, false);
+                }
+            }
+        }
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        y as usize * self.width as usize + x as usize
+    }
+}
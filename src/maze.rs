@@ -1,5 +1,15 @@
-/// Represents a single cell in the maze with its walls
-#[derive(Clone, Copy, Debug)]
+use crate::bitset::BitSet;
+use crate::events::GenerationEvent;
+use crate::grid::{Axis, CellId, Grid};
+use crate::mask::Mask;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+/// A view of the four walls around a single cell
+///
+/// `Maze` does not store cells directly; this is assembled from the
+/// passage bits of the cell and its west and north neighbours.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Cell {
     pub north: bool,
     pub south: bool,
@@ -31,26 +41,205 @@ impl Default for Cell {
     }
 }
 
-/// Represents a complete maze with a grid of cells
+/// One of the four sides of a cell
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Side {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "north" | "top" => Some(Side::North),
+            "south" | "bottom" => Some(Side::South),
+            "east" | "right" => Some(Side::East),
+            "west" | "left" => Some(Side::West),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Side::North => "north",
+            Side::South => "south",
+            Side::East => "east",
+            Side::West => "west",
+        }
+    }
+}
+
+/// The entrance or exit of a maze
+///
+/// `side` names the border wall of the cell that is opened. An endpoint
+/// without a side sits inside the maze and opens nothing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Endpoint {
+    pub x: u32,
+    pub y: u32,
+    pub side: Option<Side>,
+}
+
+impl Endpoint {
+    pub fn new(x: u32, y: u32, side: Option<Side>) -> Self {
+        Endpoint { x, y, side }
+    }
+}
+
+/// How the edges of a maze join up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Wrap {
+    /// Every edge is a wall
+    None,
+    /// The left and right edges join
+    Cylinder,
+    /// The left and right edges join, and so do the top and bottom
+    Torus,
+}
+
+impl Wrap {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "none" => Some(Wrap::None),
+            "cylinder" | "horizontal" => Some(Wrap::Cylinder),
+            "torus" | "both" => Some(Wrap::Torus),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Wrap::None => "none",
+            Wrap::Cylinder => "cylinder",
+            Wrap::Torus => "torus",
+        }
+    }
+
+    /// Whether the left and right edges join
+    pub fn wraps_x(&self) -> bool {
+        matches!(self, Wrap::Cylinder | Wrap::Torus)
+    }
+
+    /// Whether the top and bottom edges join
+    pub fn wraps_y(&self) -> bool {
+        matches!(self, Wrap::Torus)
+    }
+}
+
+/// An opening in the wall of a room
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Door {
+    /// Cell inside the room that the door opens from
+    pub x: u32,
+    pub y: u32,
+    /// Wall of that cell the door is in
+    pub side: Side,
+}
+
+/// A rectangular room carved into a maze, with the doors leading out of it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Room {
+    /// Top-left cell of the room
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub doors: Vec<Door>,
+}
+
+impl Room {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Room {
+            x,
+            y,
+            width,
+            height,
+            doors: Vec::new(),
+        }
+    }
+
+    /// Whether a cell lies inside the room
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    /// The cell at the middle of the room
+    pub fn center(&self) -> (u32, u32) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+}
+
+/// Bit offset of the passage to the east neighbour
+const EAST: usize = 0;
+/// Bit offset of the passage to the south neighbour
+const SOUTH: usize = 1;
+/// Passage bits stored per cell
+const BITS_PER_CELL: usize = 2;
+
+/// Represents a complete maze as a flat bitset of passages
+///
+/// Each cell owns one bit for the passage to its east neighbour and one for
+/// the passage to its south neighbour, so every interior wall is stored
+/// exactly once. A set bit means the wall has been removed. When the maze
+/// wraps, the last column's east bits and the last row's south bits hold the
+/// passages across the seam.
 #[derive(Clone, Debug)]
 pub struct Maze {
     width: u32,
     height: u32,
-    cells: Vec<Vec<Cell>>,
+    passages: BitSet,
+    mask: Option<Mask>,
+    start: Endpoint,
+    goal: Endpoint,
+    wrap: Wrap,
+    /// Rooms laid out by the generator, if it makes any
+    rooms: Vec<Room>,
+    /// Generation events, while recording
+    events: Option<Vec<GenerationEvent>>,
 }
 
 impl Maze {
     /// Create a new maze with all walls intact
+    ///
+    /// The entrance opens the top of the top-left cell and the exit opens
+    /// the bottom of the bottom-right cell.
     pub fn new(width: u32, height: u32) -> Self {
-        let cells = (0..height)
-            .map(|_| (0..width).map(|_| Cell::new()).collect())
-            .collect();
+        let bits = width as usize * height as usize * BITS_PER_CELL;
 
         Maze {
             width,
             height,
-            cells,
+            passages: BitSet::new(bits, false),
+            mask: None,
+            start: Endpoint::new(0, 0, Some(Side::North)),
+            goal: Endpoint::new(
+                width.saturating_sub(1),
+                height.saturating_sub(1),
+                Some(Side::South),
+            ),
+            wrap: Wrap::None,
+            rooms: Vec::new(),
+            events: None,
+        }
+    }
+
+    /// Create a new maze covering only the active cells of `mask`
+    ///
+    /// The entrance opens the top of the first active cell in reading order
+    /// and the exit opens the bottom of the last one.
+    pub fn with_mask(mask: Mask) -> Self {
+        let mut maze = Maze::new(mask.width(), mask.height());
+        maze.mask = Some(mask);
+
+        let cells = maze.active_cells();
+        if let (Some(&(sx, sy)), Some(&(gx, gy))) = (cells.first(), cells.last()) {
+            maze.start = Endpoint::new(sx, sy, Some(Side::North));
+            maze.goal = Endpoint::new(gx, gy, Some(Side::South));
         }
+
+        maze
     }
 
     /// Get the width of the maze
@@ -63,20 +252,210 @@ impl Maze {
         self.height
     }
 
-    /// Get a reference to a cell at (x, y)
-    pub fn get_cell(&self, x: u32, y: u32) -> Option<&Cell> {
-        if x < self.width && y < self.height {
-            Some(&self.cells[y as usize][x as usize])
+    /// Get the entrance of the maze
+    pub fn start(&self) -> Endpoint {
+        self.start
+    }
+
+    /// Get the exit of the maze
+    pub fn goal(&self) -> Endpoint {
+        self.goal
+    }
+
+    /// Move the entrance of the maze
+    pub fn set_start(&mut self, start: Endpoint) {
+        self.start = start;
+    }
+
+    /// Move the exit of the maze
+    pub fn set_goal(&mut self, goal: Endpoint) {
+        self.goal = goal;
+    }
+
+    /// Get how the edges of the maze join up
+    pub fn wrap(&self) -> Wrap {
+        self.wrap
+    }
+
+    /// Join the edges of the maze
+    ///
+    /// Mazes narrower than three cells along a wrapped axis are left unwrapped
+    /// there, since the cells across the seam would already be neighbours.
+    /// Endpoints whose side no longer faces out of the maze stop opening it.
+    pub fn set_wrap(&mut self, wrap: Wrap) {
+        self.wrap = wrap;
+        if !self.accepts_endpoint(&self.start) {
+            self.start.side = None;
+        }
+        if !self.accepts_endpoint(&self.goal) {
+            self.goal.side = None;
+        }
+    }
+
+    /// Column west of `x`, across the seam if the maze wraps
+    fn west_of(&self, x: u32) -> Option<u32> {
+        match x {
+            0 if self.wrap.wraps_x() && self.width > 2 => Some(self.width - 1),
+            0 => None,
+            _ => Some(x - 1),
+        }
+    }
+
+    /// Column east of `x`, across the seam if the maze wraps
+    fn east_of(&self, x: u32) -> Option<u32> {
+        if x + 1 < self.width {
+            Some(x + 1)
+        } else if self.wrap.wraps_x() && self.width > 2 {
+            Some(0)
         } else {
             None
         }
     }
 
-    /// Get a mutable reference to a cell at (x, y)
-    #[allow(dead_code)]
-    pub fn get_cell_mut(&mut self, x: u32, y: u32) -> Option<&mut Cell> {
+    /// Row north of `y`, across the seam if the maze wraps
+    fn north_of(&self, y: u32) -> Option<u32> {
+        match y {
+            0 if self.wrap.wraps_y() && self.height > 2 => Some(self.height - 1),
+            0 => None,
+            _ => Some(y - 1),
+        }
+    }
+
+    /// Row south of `y`, across the seam if the maze wraps
+    fn south_of(&self, y: u32) -> Option<u32> {
+        if y + 1 < self.height {
+            Some(y + 1)
+        } else if self.wrap.wraps_y() && self.height > 2 {
+            Some(0)
+        } else {
+            None
+        }
+    }
+
+    /// Side of the cell at (x, y) that faces the neighbouring cell (nx, ny)
+    pub fn side_facing(&self, x: u32, y: u32, nx: u32, ny: u32) -> Option<Side> {
+        if ny == y && self.east_of(x) == Some(nx) {
+            Some(Side::East)
+        } else if ny == y && self.west_of(x) == Some(nx) {
+            Some(Side::West)
+        } else if nx == x && self.south_of(y) == Some(ny) {
+            Some(Side::South)
+        } else if nx == x && self.north_of(y) == Some(ny) {
+            Some(Side::North)
+        } else {
+            None
+        }
+    }
+
+    /// Check whether a side of the cell at (x, y) is a passage across the wrap seam
+    pub fn crosses_seam(&self, x: u32, y: u32, side: Side) -> bool {
+        match side {
+            Side::North => y == 0 && self.north_of(y).is_some(),
+            Side::South => y + 1 == self.height && self.south_of(y).is_some(),
+            Side::East => x + 1 == self.width && self.east_of(x).is_some(),
+            Side::West => x == 0 && self.west_of(x).is_some(),
+        }
+    }
+
+    /// Get the mask restricting this maze, if any
+    pub fn mask(&self) -> Option<&Mask> {
+        self.mask.as_ref()
+    }
+
+    /// Check whether (x, y) is a cell of the maze (inside the grid and not masked off)
+    pub fn is_active(&self, x: u32, y: u32) -> bool {
+        x < self.width
+            && y < self.height
+            && self.mask.as_ref().is_none_or(|mask| mask.is_active(x, y))
+    }
+
+    /// Get every active cell in reading order
+    pub fn active_cells(&self) -> Vec<(u32, u32)> {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.is_active(x, y))
+            .collect()
+    }
+
+    /// Grid cell id of the cell at (x, y)
+    pub fn cell_id(&self, x: u32, y: u32) -> CellId {
+        y as usize * self.width as usize + x as usize
+    }
+
+    /// Coordinates of the cell with grid id `cell`
+    pub fn cell_coords(&self, cell: CellId) -> (u32, u32) {
+        (
+            (cell % self.width as usize) as u32,
+            (cell / self.width as usize) as u32,
+        )
+    }
+
+    /// Check whether `side` of the cell at (x, y) faces out of the maze,
+    /// either past an unwrapped grid edge or onto a masked-off cell
+    pub fn is_border(&self, x: u32, y: u32, side: Side) -> bool {
+        let outside = match side {
+            Side::North => self.north_of(y).is_none_or(|ny| !self.is_active(x, ny)),
+            Side::South => self.south_of(y).is_none_or(|ny| !self.is_active(x, ny)),
+            Side::East => self.east_of(x).is_none_or(|nx| !self.is_active(nx, y)),
+            Side::West => self.west_of(x).is_none_or(|nx| !self.is_active(nx, y)),
+        };
+        self.is_active(x, y) && outside
+    }
+
+    /// Check that an endpoint sits on an active cell and only opens a border wall
+    pub fn accepts_endpoint(&self, endpoint: &Endpoint) -> bool {
+        match endpoint.side {
+            Some(side) => self.is_border(endpoint.x, endpoint.y, side),
+            None => self.is_active(endpoint.x, endpoint.y),
+        }
+    }
+
+    /// Index of the passage bit `dir` of cell (x, y)
+    fn bit_index(&self, x: u32, y: u32, dir: usize) -> usize {
+        (y as usize * self.width as usize + x as usize) * BITS_PER_CELL + dir
+    }
+
+    /// Check whether the passage bit `dir` of cell (x, y) is open
+    fn is_open(&self, x: u32, y: u32, dir: usize) -> bool {
+        self.passages.get(self.bit_index(x, y, dir))
+    }
+
+    /// Open or close the passage bit `dir` of cell (x, y)
+    fn set_open(&mut self, x: u32, y: u32, dir: usize, open: bool) {
+        let bit = self.bit_index(x, y, dir);
+        self.passages.set(bit, open);
+    }
+
+    /// Resolve the wall between two cells to the cell and bit that store it
+    ///
+    /// Returns None if either cell is outside the maze or they are not adjacent.
+    fn wall_bit(&self, x1: u32, y1: u32, x2: u32, y2: u32) -> Option<(u32, u32, usize)> {
+        if !self.is_active(x1, y1) || !self.is_active(x2, y2) {
+            return None;
+        }
+
+        if y1 == y2 && self.east_of(x1) == Some(x2) {
+            Some((x1, y1, EAST))
+        } else if y1 == y2 && self.east_of(x2) == Some(x1) {
+            Some((x2, y2, EAST))
+        } else if x1 == x2 && self.south_of(y1) == Some(y2) {
+            Some((x1, y1, SOUTH))
+        } else if x1 == x2 && self.south_of(y2) == Some(y1) {
+            Some((x2, y2, SOUTH))
+        } else {
+            None
+        }
+    }
+
+    /// Get a view of the walls around the cell at (x, y)
+    pub fn get_cell(&self, x: u32, y: u32) -> Option<Cell> {
         if x < self.width && y < self.height {
-            Some(&mut self.cells[y as usize][x as usize])
+            Some(Cell {
+                north: self.north_of(y).is_none_or(|ny| !self.is_open(x, ny, SOUTH)),
+                south: !self.is_open(x, y, SOUTH),
+                east: !self.is_open(x, y, EAST),
+                west: self.west_of(x).is_none_or(|nx| !self.is_open(nx, y, EAST)),
+            })
         } else {
             None
         }
@@ -84,80 +463,91 @@ impl Maze {
 
     /// Remove the wall between two adjacent cells
     pub fn remove_wall(&mut self, x1: u32, y1: u32, x2: u32, y2: u32) {
-        if x1 >= self.width || y1 >= self.height || x2 >= self.width || y2 >= self.height {
-            return;
+        if let Some((x, y, dir)) = self.wall_bit(x1, y1, x2, y2) {
+            if !self.is_open(x, y, dir) {
+                let event = GenerationEvent::Carve(self.cell_id(x1, y1), self.cell_id(x2, y2));
+                self.record(event);
+            }
+            self.set_open(x, y, dir, true);
         }
+    }
 
-        let dx = x2 as i32 - x1 as i32;
-        let dy = y2 as i32 - y1 as i32;
-
-        // Check if cells are adjacent
-        if (dx == 0 && dy.abs() == 1) || (dy == 0 && dx.abs() == 1) {
-            let y1_idx = y1 as usize;
-            let x1_idx = x1 as usize;
-            let y2_idx = y2 as usize;
-            let x2_idx = x2 as usize;
-
-            if dx == 1 {
-                // cell2 is east of cell1
-                self.cells[y1_idx][x1_idx].east = false;
-                self.cells[y2_idx][x2_idx].west = false;
-            } else if dx == -1 {
-                // cell2 is west of cell1
-                self.cells[y1_idx][x1_idx].west = false;
-                self.cells[y2_idx][x2_idx].east = false;
-            } else if dy == 1 {
-                // cell2 is south of cell1
-                self.cells[y1_idx][x1_idx].south = false;
-                self.cells[y2_idx][x2_idx].north = false;
-            } else if dy == -1 {
-                // cell2 is north of cell1
-                self.cells[y1_idx][x1_idx].north = false;
-                self.cells[y2_idx][x2_idx].south = false;
+    /// Put back the wall between two adjacent cells
+    pub fn add_wall(&mut self, x1: u32, y1: u32, x2: u32, y2: u32) {
+        if let Some((x, y, dir)) = self.wall_bit(x1, y1, x2, y2) {
+            if self.is_open(x, y, dir) {
+                let event = GenerationEvent::AddWall(self.cell_id(x1, y1), self.cell_id(x2, y2));
+                self.record(event);
             }
+            self.set_open(x, y, dir, false);
+        }
+    }
+
+    /// Rooms the generator laid out, such as the rooms of a dungeon
+    pub fn rooms(&self) -> &[Room] {
+        &self.rooms
+    }
+
+    /// Record the rooms laid out in the maze
+    pub fn set_rooms(&mut self, rooms: Vec<Room>) {
+        self.rooms = rooms;
+    }
+
+    /// Start recording generation events, discarding any recorded before
+    pub fn record_events(&mut self) {
+        self.events = Some(Vec::new());
+    }
+
+    /// Whether generation events are being recorded
+    pub fn is_recording_events(&self) -> bool {
+        self.events.is_some()
+    }
+
+    /// Stop recording and hand back the events recorded since `record_events`
+    pub fn take_events(&mut self) -> Vec<GenerationEvent> {
+        self.events.take().unwrap_or_default()
+    }
+
+    /// Note an event if recording
+    fn record(&mut self, event: GenerationEvent) {
+        if let Some(events) = &mut self.events {
+            events.push(event);
         }
     }
 
     /// Check if there's a wall between two adjacent cells
     #[allow(dead_code)]
     pub fn has_wall(&self, x1: u32, y1: u32, x2: u32, y2: u32) -> bool {
-        if x1 >= self.width || y1 >= self.height || x2 >= self.width || y2 >= self.height {
-            return true;
-        }
-
-        let dx = x2 as i32 - x1 as i32;
-        let dy = y2 as i32 - y1 as i32;
-
-        if let Some(cell) = self.get_cell(x1, y1) {
-            if dx == 1 {
-                return cell.east;
-            } else if dx == -1 {
-                return cell.west;
-            } else if dy == 1 {
-                return cell.south;
-            } else if dy == -1 {
-                return cell.north;
-            }
+        match self.wall_bit(x1, y1, x2, y2) {
+            Some((x, y, dir)) => !self.is_open(x, y, dir),
+            None => true,
         }
-
-        true
     }
 
     /// Get all valid neighbors of a cell
+    /// Masked-off cells are never neighbors; wrapped mazes include the cells across the seam.
     pub fn get_neighbors(&self, x: u32, y: u32) -> Vec<(u32, u32)> {
         let mut neighbors = Vec::new();
 
-        if x > 0 {
-            neighbors.push((x - 1, y));
+        if !self.is_active(x, y) {
+            return neighbors;
+        }
+
+        if let Some(nx) = self.west_of(x) {
+            neighbors.push((nx, y));
+        }
+        if let Some(nx) = self.east_of(x) {
+            neighbors.push((nx, y));
         }
-        if x < self.width - 1 {
-            neighbors.push((x + 1, y));
+        if let Some(ny) = self.north_of(y) {
+            neighbors.push((x, ny));
         }
-        if y > 0 {
-            neighbors.push((x, y - 1));
+        if let Some(ny) = self.south_of(y) {
+            neighbors.push((x, ny));
         }
-        if y < self.height - 1 {
-            neighbors.push((x, y + 1));
+
+        if self.mask.is_some() {
+            neighbors.retain(|&(nx, ny)| self.is_active(nx, ny));
         }
 
         neighbors
@@ -167,35 +557,43 @@ impl Maze {
     pub fn get_accessible_neighbors(&self, x: u32, y: u32) -> Vec<(u32, u32)> {
         let mut accessible = Vec::new();
 
-        if let Some(cell) = self.get_cell(x, y) {
-            // Check west
-            if x > 0 && !cell.west {
-                accessible.push((x - 1, y));
-            }
-            // Check east
-            if x < self.width - 1 && !cell.east {
-                accessible.push((x + 1, y));
-            }
-            // Check north
-            if y > 0 && !cell.north {
-                accessible.push((x, y - 1));
-            }
-            // Check south
-            if y < self.height - 1 && !cell.south {
-                accessible.push((x, y + 1));
-            }
+        if x >= self.width || y >= self.height {
+            return accessible;
+        }
+
+        // Check west
+        if let Some(nx) = self.west_of(x).filter(|&nx| self.is_open(nx, y, EAST)) {
+            accessible.push((nx, y));
+        }
+        // Check east
+        if let Some(nx) = self.east_of(x).filter(|_| self.is_open(x, y, EAST)) {
+            accessible.push((nx, y));
+        }
+        // Check north
+        if let Some(ny) = self.north_of(y).filter(|&ny| self.is_open(x, ny, SOUTH)) {
+            accessible.push((x, ny));
+        }
+        // Check south
+        if let Some(ny) = self.south_of(y).filter(|_| self.is_open(x, y, SOUTH)) {
+            accessible.push((x, ny));
         }
 
         accessible
     }
 
-    /// Ensure there is a path from (0,0) to (width-1, height-1)
+    /// Ensure there is a path from the start cell to the goal cell
     /// This method carves a path if one doesn't already exist
     pub fn ensure_connectivity(&mut self) {
-        use std::collections::{HashSet, VecDeque};
+        use std::collections::hash_map::Entry;
+        use std::collections::{HashMap, HashSet, VecDeque};
+
+        // An empty maze has nothing to connect
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
 
-        let start = (0, 0);
-        let end = (self.width - 1, self.height - 1);
+        let start = (self.start.x, self.start.y);
+        let end = (self.goal.x, self.goal.y);
 
         // First, check if there's already a path
         let mut queue = VecDeque::new();
@@ -218,21 +616,7 @@ impl Maze {
         }
 
         // No path exists, need to create one
-        // Find the component containing start
-        let mut start_component = HashSet::new();
-        let mut queue = VecDeque::new();
-        queue.push_back(start);
-        start_component.insert(start);
-
-        while let Some(current) = queue.pop_front() {
-            for neighbor in self.get_accessible_neighbors(current.0, current.1) {
-                if !start_component.contains(&neighbor) {
-                    start_component.insert(neighbor);
-                    queue.push_back(neighbor);
-                }
-            }
-        }
-
+        // The search above has filled `visited` with the component containing start
         // Find the component containing end
         let mut end_component = HashSet::new();
         let mut queue = VecDeque::new();
@@ -248,45 +632,52 @@ impl Maze {
             }
         }
 
-        // Find the closest pair of cells between the two components
-        let mut min_dist = u32::MAX;
-        let mut best_pair = None;
+        // Search outward from the start component, ignoring walls, for the
+        // nearest cell of the end component. Only active cells are searched,
+        // so the carved path never leaves the maze.
+        // Sources are sorted so the carved path does not depend on hash order.
+        let mut sources: Vec<(u32, u32)> = visited.into_iter().collect();
+        sources.sort_unstable_by_key(|&(x, y)| (y, x));
 
-        for &(x1, y1) in &start_component {
-            for &(x2, y2) in &end_component {
-                let dist = (x1 as i32 - x2 as i32).abs() + (y1 as i32 - y2 as i32).abs();
-                if dist < min_dist as i32 {
-                    min_dist = dist as u32;
-                    best_pair = Some((x1, y1, x2, y2));
-                }
-            }
+        let mut came_from = HashMap::new();
+        for &cell in &sources {
+            came_from.insert(cell, None);
         }
+        let mut queue: VecDeque<(u32, u32)> = sources.into_iter().collect();
 
-        // Carve a path between the two components
-        let (x1, y1, x2, y2) = best_pair.unwrap_or((start.0, start.1, end.0, end.1));
-        let mut cx = x1;
-        let mut cy = y1;
+        let mut meeting = None;
+        while let Some(current) = queue.pop_front() {
+            if end_component.contains(&current) {
+                meeting = Some(current);
+                break;
+            }
 
-        // Move horizontally first, then vertically
-        while cx != x2 {
-            let next_x = if cx < x2 { cx + 1 } else { cx - 1 };
-            self.remove_wall(cx, cy, next_x, cy);
-            cx = next_x;
+            for neighbor in self.get_neighbors(current.0, current.1) {
+                if let Entry::Vacant(entry) = came_from.entry(neighbor) {
+                    entry.insert(Some(current));
+                    queue.push_back(neighbor);
+                }
+            }
         }
 
-        while cy != y2 {
-            let next_y = if cy < y2 { cy + 1 } else { cy - 1 };
-            self.remove_wall(cx, cy, cx, next_y);
-            cy = next_y;
+        // Carve the path back to the start component
+        let mut node = meeting;
+        while let Some(current) = node {
+            let previous = came_from[&current];
+            if let Some(prev) = previous {
+                self.remove_wall(prev.0, prev.1, current.0, current.1);
+            }
+            node = previous;
         }
     }
 
-    /// Solve the maze using BFS, returning the path from (0,0) to (width-1, height-1)
+    /// Solve the maze using BFS, returning the path from the start cell to the goal cell
     pub fn solve(&self) -> Option<Vec<(u32, u32)>> {
+        use std::collections::hash_map::Entry;
         use std::collections::{HashMap, VecDeque};
 
-        let start = (0, 0);
-        let end = (self.width - 1, self.height - 1);
+        let start = (self.start.x, self.start.y);
+        let end = (self.goal.x, self.goal.y);
 
         let mut queue = VecDeque::new();
         queue.push_back(start);
@@ -308,8 +699,8 @@ impl Maze {
             }
 
             for neighbor in self.get_accessible_neighbors(current.0, current.1) {
-                if !visited.contains_key(&neighbor) {
-                    visited.insert(neighbor, Some(current));
+                if let Entry::Vacant(entry) = visited.entry(neighbor) {
+                    entry.insert(Some(current));
                     queue.push_back(neighbor);
                 }
             }
@@ -319,3 +710,108 @@ impl Maze {
     }
 }
 
+impl Grid for Maze {
+    fn cell_count(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    fn is_active(&self, cell: CellId) -> bool {
+        let (x, y) = self.cell_coords(cell);
+        Maze::is_active(self, x, y)
+    }
+
+    fn neighbors(&self, cell: CellId) -> Vec<CellId> {
+        let (x, y) = self.cell_coords(cell);
+        self.get_neighbors(x, y)
+            .into_iter()
+            .map(|(nx, ny)| self.cell_id(nx, ny))
+            .collect()
+    }
+
+    fn link(&mut self, a: CellId, b: CellId) {
+        let (x1, y1) = self.cell_coords(a);
+        let (x2, y2) = self.cell_coords(b);
+        self.remove_wall(x1, y1, x2, y2);
+    }
+
+    fn unlink(&mut self, a: CellId, b: CellId) {
+        let (x1, y1) = self.cell_coords(a);
+        let (x2, y2) = self.cell_coords(b);
+        self.add_wall(x1, y1, x2, y2);
+    }
+
+    fn is_linked(&self, a: CellId, b: CellId) -> bool {
+        let (x1, y1) = self.cell_coords(a);
+        let (x2, y2) = self.cell_coords(b);
+        !self.has_wall(x1, y1, x2, y2)
+    }
+
+    fn is_boundary(&self, cell: CellId) -> bool {
+        let (x, y) = self.cell_coords(cell);
+        [Side::North, Side::South, Side::East, Side::West]
+            .into_iter()
+            .any(|side| self.is_border(x, y, side))
+    }
+
+    fn links(&self, cell: CellId) -> Vec<CellId> {
+        let (x, y) = self.cell_coords(cell);
+        self.get_accessible_neighbors(x, y)
+            .into_iter()
+            .map(|(nx, ny)| self.cell_id(nx, ny))
+            .collect()
+    }
+
+    fn cells(&self) -> Vec<CellId> {
+        self.active_cells()
+            .into_iter()
+            .map(|(x, y)| self.cell_id(x, y))
+            .collect()
+    }
+
+    fn active_count(&self) -> usize {
+        match &self.mask {
+            Some(mask) => mask.active_count(),
+            None => self.cell_count(),
+        }
+    }
+
+    /// Unmasked mazes draw x then y directly, so seeds keep producing the same mazes
+    fn random_cell(&self, rng: &mut ChaCha8Rng) -> CellId {
+        if self.mask.is_none() {
+            let x = rng.gen_range(0..self.width);
+            let y = rng.gen_range(0..self.height);
+            return self.cell_id(x, y);
+        }
+
+        let cells = self.cells();
+        cells[rng.gen_range(0..cells.len())]
+    }
+
+    fn axis(&self, a: CellId, b: CellId) -> Option<Axis> {
+        let (ax, ay) = self.cell_coords(a);
+        let (bx, by) = self.cell_coords(b);
+        if ay == by {
+            Some(Axis::Horizontal)
+        } else if ax == bx {
+            Some(Axis::Vertical)
+        } else {
+            None
+        }
+    }
+
+    fn position(&self, cell: CellId) -> Option<(f64, f64)> {
+        let (x, y) = self.cell_coords(cell);
+        Some((
+            (x as f64 + 0.5) / self.width() as f64,
+            (y as f64 + 0.5) / self.height() as f64,
+        ))
+    }
+
+    fn visit(&mut self, cell: CellId) {
+        self.record(GenerationEvent::Visit(cell));
+    }
+
+    fn backtrack(&mut self, cell: CellId) {
+        self.record(GenerationEvent::Backtrack(cell));
+    }
+}
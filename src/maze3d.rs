@@ -0,0 +1,290 @@
+use crate::bitset::BitSet;
+use crate::grid::{Axis, CellId, Grid, Point, Tiling};
+
+/// Passage bits stored per cell
+const EAST: usize = 0;
+const SOUTH: usize = 1;
+const UP: usize = 2;
+const BITS_PER_CELL: usize = 3;
+
+/// A stack of square-grid levels joined by stairs
+///
+/// Cell (x, y, level) links to its four neighbours on the same level and to
+/// the cells directly above and below it. Passages are stored once, as east,
+/// south and up bits on the lower-numbered cell. The entrance is the north
+/// wall of (0, 0) on the first level and the exit the south wall of the
+/// bottom-right cell on the last level.
+#[derive(Clone, Debug)]
+pub struct Maze3d {
+    width: u32,
+    height: u32,
+    levels: u32,
+    passages: BitSet,
+}
+
+impl Maze3d {
+    /// Create a new multi-level maze with all walls present
+    pub fn new(width: u32, height: u32, levels: u32) -> Self {
+        Maze3d {
+            width,
+            height,
+            levels,
+            passages: BitSet::new(
+                width as usize * height as usize * levels as usize * BITS_PER_CELL,
+                false,
+            ),
+        }
+    }
+
+    /// Get the width of each level in cells
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get the height of each level in cells
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Get the number of levels
+    pub fn levels(&self) -> u32 {
+        self.levels
+    }
+
+    /// Convert (x, y, level) to a cell id
+    pub fn cell_id(&self, x: u32, y: u32, level: u32) -> CellId {
+        (level as usize * self.height as usize + y as usize) * self.width as usize + x as usize
+    }
+
+    /// Convert a cell id back to (x, y, level)
+    pub fn cell_coords(&self, cell: CellId) -> (u32, u32, u32) {
+        let width = self.width as usize;
+        let plane = width * self.height as usize;
+        (
+            (cell % width) as u32,
+            (cell % plane / width) as u32,
+            (cell / plane) as u32,
+        )
+    }
+
+    /// Whether a cell has stairs to the level above
+    pub fn has_stairs_up(&self, x: u32, y: u32, level: u32) -> bool {
+        level + 1 < self.levels && self.passages.get(self.cell_id(x, y, level) * BITS_PER_CELL + UP)
+    }
+
+    /// Whether a cell has stairs to the level below
+    pub fn has_stairs_down(&self, x: u32, y: u32, level: u32) -> bool {
+        level > 0 && self.has_stairs_up(x, y, level - 1)
+    }
+
+    /// Bit holding the passage between two adjacent cells
+    fn passage_bit(&self, a: CellId, b: CellId) -> Option<usize> {
+        let (low, high) = (a.min(b), a.max(b));
+        let (lx, ly, lz) = self.cell_coords(low);
+        let (hx, hy, hz) = self.cell_coords(high);
+
+        let direction = if lz == hz && ly == hy && lx + 1 == hx {
+            EAST
+        } else if lz == hz && lx == hx && ly + 1 == hy {
+            SOUTH
+        } else if lx == hx && ly == hy && lz + 1 == hz {
+            UP
+        } else {
+            return None;
+        };
+        Some(low * BITS_PER_CELL + direction)
+    }
+
+    /// Pixel position of a cell corner, with levels drawn left to right
+    /// and one empty column between them
+    fn corner(&self, x: u32, y: u32, level: u32, cell_size: u32) -> Point {
+        let column = level * (self.width + 1) + x;
+        ((column * cell_size) as f64, (y * cell_size) as f64)
+    }
+}
+
+impl Grid for Maze3d {
+    fn cell_count(&self) -> usize {
+        self.width as usize * self.height as usize * self.levels as usize
+    }
+
+    /// Neighbours in the order west, east, north, south, down, up
+    fn neighbors(&self, cell: CellId) -> Vec<CellId> {
+        let (x, y, level) = self.cell_coords(cell);
+        let mut neighbors = Vec::new();
+
+        if x > 0 {
+            neighbors.push(self.cell_id(x - 1, y, level));
+        }
+        if x + 1 < self.width {
+            neighbors.push(self.cell_id(x + 1, y, level));
+        }
+        if y > 0 {
+            neighbors.push(self.cell_id(x, y - 1, level));
+        }
+        if y + 1 < self.height {
+            neighbors.push(self.cell_id(x, y + 1, level));
+        }
+        if level > 0 {
+            neighbors.push(self.cell_id(x, y, level - 1));
+        }
+        if level + 1 < self.levels {
+            neighbors.push(self.cell_id(x, y, level + 1));
+        }
+
+        neighbors
+    }
+
+    fn link(&mut self, a: CellId, b: CellId) {
+        if let Some(bit) = self.passage_bit(a, b) {
+            self.passages.set(bit, true);
+        }
+    }
+
+    fn unlink(&mut self, a: CellId, b: CellId) {
+        if let Some(bit) = self.passage_bit(a, b) {
+            self.passages.set(bit, false);
+        }
+    }
+
+    fn is_linked(&self, a: CellId, b: CellId) -> bool {
+        self.passage_bit(a, b)
+            .map(|bit| self.passages.get(bit))
+            .unwrap_or(false)
+    }
+
+    /// Only the side walls count as the boundary; the floor and ceiling
+    /// of a level are never opened to the outside
+    fn is_boundary(&self, cell: CellId) -> bool {
+        let (x, y, _) = self.cell_coords(cell);
+        x == 0 || y == 0 || x + 1 == self.width || y + 1 == self.height
+    }
+
+    /// Stairs between levels run neither way
+    fn axis(&self, a: CellId, b: CellId) -> Option<Axis> {
+        let (ax, ay, al) = self.cell_coords(a);
+        let (bx, by, bl) = self.cell_coords(b);
+        if al != bl {
+            None
+        } else if ay == by {
+            Some(Axis::Horizontal)
+        } else if ax == bx {
+            Some(Axis::Vertical)
+        } else {
+            None
+        }
+    }
+
+    /// Every level covers the same square, so stacked cells share a position
+    fn position(&self, cell: CellId) -> Option<(f64, f64)> {
+        let (x, y, _) = self.cell_coords(cell);
+        Some((
+            (x as f64 + 0.5) / self.width() as f64,
+            (y as f64 + 0.5) / self.height() as f64,
+        ))
+    }
+}
+
+impl Tiling for Maze3d {
+    fn name(&self) -> &'static str {
+        "square (multi-level)"
+    }
+
+    fn start(&self) -> CellId {
+        0
+    }
+
+    fn goal(&self) -> CellId {
+        self.cell_count().saturating_sub(1)
+    }
+
+    fn canvas_size(&self, cell_size: u32) -> (u32, u32) {
+        let columns = (self.width + 1) * self.levels - 1;
+        (columns * cell_size + 1, self.height * cell_size + 1)
+    }
+
+    fn center(&self, cell: CellId, cell_size: u32) -> Point {
+        let (x, y, level) = self.cell_coords(cell);
+        let (px, py) = self.corner(x, y, level, cell_size);
+        let half = cell_size as f64 / 2.0;
+        (px + half, py + half)
+    }
+
+    /// Each cell draws its east and south walls; the west and north walls
+    /// are only drawn on the edge of a level
+    fn walls(&self, cell_size: u32) -> Vec<(Point, Point)> {
+        let mut walls = Vec::new();
+
+        for cell in 0..self.cell_count() {
+            let (x, y, level) = self.cell_coords(cell);
+            let nw = self.corner(x, y, level, cell_size);
+            let ne = self.corner(x + 1, y, level, cell_size);
+            let sw = self.corner(x, y + 1, level, cell_size);
+            let se = self.corner(x + 1, y + 1, level, cell_size);
+
+            if y == 0 && cell != self.start() {
+                walls.push((nw, ne));
+            }
+            if x == 0 {
+                walls.push((nw, sw));
+            }
+            if x + 1 == self.width || !self.is_linked(cell, cell + 1) {
+                walls.push((ne, se));
+            }
+            let south_open = if y + 1 < self.height {
+                self.is_linked(cell, self.cell_id(x, y + 1, level))
+            } else {
+                cell == self.goal()
+            };
+            if !south_open {
+                walls.push((sw, se));
+            }
+        }
+
+        walls
+    }
+
+    /// An upward chevron above the centre of cells with stairs up, and a
+    /// downward one below the centre of cells with stairs down
+    fn markers(&self, cell_size: u32) -> Vec<(Point, Point)> {
+        let mut markers = Vec::new();
+        let size = cell_size as f64 * 0.2;
+
+        for cell in 0..self.cell_count() {
+            let (x, y, level) = self.cell_coords(cell);
+            let (cx, cy) = self.center(cell, cell_size);
+
+            for (has_stairs, direction) in [
+                (self.has_stairs_up(x, y, level), -1.0),
+                (self.has_stairs_down(x, y, level), 1.0),
+            ] {
+                if has_stairs {
+                    let tip = (cx, cy + direction * size * 1.5);
+                    markers.push(((cx - size, cy + direction * size * 0.5), tip));
+                    markers.push((tip, (cx + size, cy + direction * size * 0.5)));
+                }
+            }
+        }
+
+        markers
+    }
+
+    /// A new line starts in the next panel wherever the path takes the stairs
+    fn path(&self, cells: &[CellId], cell_size: u32) -> Vec<Vec<Point>> {
+        let mut lines: Vec<Vec<Point>> = Vec::new();
+        let mut current_level = None;
+
+        for &cell in cells {
+            let (_, _, level) = self.cell_coords(cell);
+            if current_level != Some(level) {
+                lines.push(Vec::new());
+                current_level = Some(level);
+            }
+            if let Some(line) = lines.last_mut() {
+                line.push(self.center(cell, cell_size));
+            }
+        }
+
+        lines
+    }
+}
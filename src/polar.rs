@@ -0,0 +1,361 @@
+use crate::bitset::BitSet;
+use crate::grid::{Axis, CellId, Grid, Point, Tiling};
+use std::f64::consts::TAU;
+
+/// Passage bits stored per cell
+const CLOCKWISE: usize = 0;
+const INWARD: usize = 1;
+const BITS_PER_CELL: usize = 2;
+
+/// Where a polar maze is entered
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PolarEntrance {
+    /// Start in the centre and leave through the bottom of the rim
+    Center,
+    /// Enter through the top of the rim and finish in the centre
+    Rim,
+}
+
+impl PolarEntrance {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "center" | "centre" => Some(PolarEntrance::Center),
+            "rim" | "edge" => Some(PolarEntrance::Rim),
+            _ => None,
+        }
+    }
+}
+
+/// A circular maze of concentric rings around a single centre cell
+///
+/// Ring `r` is split into more cells than ring `r - 1` whenever the cells
+/// would otherwise grow much wider than they are deep, so every cell has
+/// roughly the same size. Each cell of an outer ring has exactly one inward
+/// neighbour, and cells are numbered clockwise from north.
+#[derive(Clone, Debug)]
+pub struct PolarMaze {
+    ring_offsets: Vec<usize>,
+    passages: BitSet,
+    entrance: PolarEntrance,
+}
+
+impl PolarMaze {
+    /// Create a new polar maze with `rings` rings, counting the centre, and all walls present
+    pub fn new(rings: u32, entrance: PolarEntrance) -> Self {
+        let rings = rings.max(1) as usize;
+        let mut ring_offsets = vec![0, 1];
+
+        for ring in 1..rings {
+            let previous = ring_offsets[ring] - ring_offsets[ring - 1];
+            // Split when the cells would be more than about twice as wide as deep
+            let ratio = ((TAU * ring as f64) / previous as f64).round().max(1.0) as usize;
+            ring_offsets.push(ring_offsets[ring] + previous * ratio);
+        }
+
+        let cells = *ring_offsets.last().unwrap();
+        PolarMaze {
+            ring_offsets,
+            passages: BitSet::new(cells * BITS_PER_CELL, false),
+            entrance,
+        }
+    }
+
+    /// Number of rings, counting the centre cell as ring 0
+    pub fn rings(&self) -> u32 {
+        (self.ring_offsets.len() - 1) as u32
+    }
+
+    /// Number of cells in a ring
+    pub fn ring_size(&self, ring: u32) -> usize {
+        self.ring_offsets[ring as usize + 1] - self.ring_offsets[ring as usize]
+    }
+
+    /// Convert (ring, index) to a cell id
+    pub fn cell_id(&self, ring: u32, index: usize) -> CellId {
+        self.ring_offsets[ring as usize] + index
+    }
+
+    /// Convert a cell id back to (ring, index)
+    pub fn cell_coords(&self, cell: CellId) -> (u32, usize) {
+        let ring = self.ring_offsets.partition_point(|&offset| offset <= cell) - 1;
+        (ring as u32, cell - self.ring_offsets[ring])
+    }
+
+    /// Next cell clockwise in the same ring
+    fn clockwise(&self, cell: CellId) -> Option<CellId> {
+        let (ring, index) = self.cell_coords(cell);
+        let size = self.ring_size(ring);
+        (size > 1).then(|| self.cell_id(ring, (index + 1) % size))
+    }
+
+    /// Next cell counter-clockwise in the same ring
+    fn counter_clockwise(&self, cell: CellId) -> Option<CellId> {
+        let (ring, index) = self.cell_coords(cell);
+        let size = self.ring_size(ring);
+        (size > 1).then(|| self.cell_id(ring, (index + size - 1) % size))
+    }
+
+    /// The single cell of the next ring in
+    fn inward(&self, cell: CellId) -> Option<CellId> {
+        let (ring, index) = self.cell_coords(cell);
+        if ring == 0 {
+            return None;
+        }
+        let ratio = self.ring_size(ring) / self.ring_size(ring - 1);
+        Some(self.cell_id(ring - 1, index / ratio))
+    }
+
+    /// The cells of the next ring out that share this cell's outer wall
+    fn outward(&self, cell: CellId) -> Vec<CellId> {
+        let (ring, index) = self.cell_coords(cell);
+        if ring + 1 >= self.rings() {
+            return Vec::new();
+        }
+        let ratio = self.ring_size(ring + 1) / self.ring_size(ring);
+        (index * ratio..(index + 1) * ratio)
+            .map(|i| self.cell_id(ring + 1, i))
+            .collect()
+    }
+
+    /// Bit holding the passage between two adjacent cells
+    /// Ring passages belong to the counter-clockwise cell, radial ones to the outer cell.
+    fn passage_bit(&self, a: CellId, b: CellId) -> Option<usize> {
+        if self.clockwise(a) == Some(b) {
+            Some(a * BITS_PER_CELL + CLOCKWISE)
+        } else if self.clockwise(b) == Some(a) {
+            Some(b * BITS_PER_CELL + CLOCKWISE)
+        } else if self.inward(a) == Some(b) {
+            Some(a * BITS_PER_CELL + INWARD)
+        } else if self.inward(b) == Some(a) {
+            Some(b * BITS_PER_CELL + INWARD)
+        } else {
+            None
+        }
+    }
+
+    /// Rim cell with the opening: at the top for a rim entrance, else at the bottom
+    fn rim_opening(&self) -> CellId {
+        let ring = self.rings() - 1;
+        match self.entrance {
+            PolarEntrance::Rim => self.cell_id(ring, 0),
+            PolarEntrance::Center => self.cell_id(ring, self.ring_size(ring) / 2),
+        }
+    }
+
+    /// Angles at the counter-clockwise and clockwise edges of a cell
+    fn angles(&self, cell: CellId) -> (f64, f64) {
+        let (ring, index) = self.cell_coords(cell);
+        let step = TAU / self.ring_size(ring) as f64;
+        (index as f64 * step, (index + 1) as f64 * step)
+    }
+
+    /// Angle through the middle of a cell
+    fn mid_angle(&self, cell: CellId) -> f64 {
+        let (from, to) = self.angles(cell);
+        (from + to) / 2.0
+    }
+
+    /// Radius through the middle of a cell's ring
+    fn mid_radius(&self, cell: CellId, cell_size: u32) -> f64 {
+        (self.cell_coords(cell).0 as f64 + 0.5) * cell_size as f64
+    }
+
+    /// Points along an arc between two angles the short way round, without the first
+    fn turn(&self, from: f64, to: f64, radius: f64, cell_size: u32) -> Vec<Point> {
+        let to = if to - from > TAU / 2.0 {
+            to - TAU
+        } else if from - to > TAU / 2.0 {
+            to + TAU
+        } else {
+            to
+        };
+        self.arc(from, to, radius, cell_size).split_off(1)
+    }
+
+    /// Pixel position of an angle (clockwise from north) and radius
+    fn point(&self, angle: f64, radius: f64, cell_size: u32) -> Point {
+        let middle = self.rings() as f64 * cell_size as f64;
+        (middle + radius * angle.sin(), middle - radius * angle.cos())
+    }
+
+    /// An arc split into short straight segments
+    fn arc(&self, from: f64, to: f64, radius: f64, cell_size: u32) -> Vec<Point> {
+        let steps = ((to - from).abs() * radius / 3.0).ceil().max(1.0) as usize;
+        (0..=steps)
+            .map(|i| {
+                let angle = from + (to - from) * i as f64 / steps as f64;
+                self.point(angle, radius, cell_size)
+            })
+            .collect()
+    }
+}
+
+impl Grid for PolarMaze {
+    fn cell_count(&self) -> usize {
+        *self.ring_offsets.last().unwrap()
+    }
+
+    fn neighbors(&self, cell: CellId) -> Vec<CellId> {
+        let mut neighbors: Vec<CellId> = [
+            self.clockwise(cell),
+            self.counter_clockwise(cell),
+            self.inward(cell),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        neighbors.extend(self.outward(cell));
+        // A ring of two cells is its own clockwise and counter-clockwise neighbour
+        neighbors.dedup();
+        neighbors
+    }
+
+    fn link(&mut self, a: CellId, b: CellId) {
+        if let Some(bit) = self.passage_bit(a, b) {
+            self.passages.set(bit, true);
+        }
+    }
+
+    fn unlink(&mut self, a: CellId, b: CellId) {
+        if let Some(bit) = self.passage_bit(a, b) {
+            self.passages.set(bit, false);
+        }
+    }
+
+    fn is_linked(&self, a: CellId, b: CellId) -> bool {
+        self.passage_bit(a, b)
+            .map(|bit| self.passages.get(bit))
+            .unwrap_or(false)
+    }
+
+    fn is_boundary(&self, cell: CellId) -> bool {
+        self.cell_coords(cell).0 + 1 == self.rings()
+    }
+
+    /// Passages around a ring run horizontally, and between rings vertically
+    fn axis(&self, a: CellId, b: CellId) -> Option<Axis> {
+        if self.cell_coords(a).0 == self.cell_coords(b).0 {
+            Some(Axis::Horizontal)
+        } else {
+            Some(Axis::Vertical)
+        }
+    }
+
+    /// Where the cell is drawn, scaled to the square the disc fits in
+    fn position(&self, cell: CellId) -> Option<(f64, f64)> {
+        let (x, y) = self.center(cell, 1);
+        let (size, _) = self.canvas_size(1);
+        Some((x / size as f64, y / size as f64))
+    }
+}
+
+impl Tiling for PolarMaze {
+    fn name(&self) -> &'static str {
+        "polar"
+    }
+
+    fn start(&self) -> CellId {
+        match self.entrance {
+            PolarEntrance::Center => 0,
+            PolarEntrance::Rim => self.rim_opening(),
+        }
+    }
+
+    fn goal(&self) -> CellId {
+        match self.entrance {
+            PolarEntrance::Center => self.rim_opening(),
+            PolarEntrance::Rim => 0,
+        }
+    }
+
+    /// Each ring is `cell_size` pixels deep
+    fn canvas_size(&self, cell_size: u32) -> (u32, u32) {
+        let size = 2 * self.rings() * cell_size + 1;
+        (size, size)
+    }
+
+    /// The centre cell's centre is the middle of the image; other cells use
+    /// the middle of their ring and of their arc
+    fn center(&self, cell: CellId, cell_size: u32) -> Point {
+        let (ring, _) = self.cell_coords(cell);
+        if ring == 0 {
+            return self.point(0.0, 0.0, cell_size);
+        }
+        self.point(self.mid_angle(cell), self.mid_radius(cell, cell_size), cell_size)
+    }
+
+    /// Each cell draws its inner arc and clockwise radial wall; the outer
+    /// ring also draws the rim
+    fn walls(&self, cell_size: u32) -> Vec<(Point, Point)> {
+        let mut walls = Vec::new();
+        let size = cell_size as f64;
+        let mut push_arc = |points: Vec<Point>| {
+            walls.extend(points.windows(2).map(|pair| (pair[0], pair[1])));
+        };
+
+        for cell in 1..self.cell_count() {
+            let (ring, _) = self.cell_coords(cell);
+            let (from, to) = self.angles(cell);
+            let inner = ring as f64 * size;
+            let outer = inner + size;
+
+            if let Some(inward) = self.inward(cell) {
+                if !self.is_linked(cell, inward) {
+                    push_arc(self.arc(from, to, inner, cell_size));
+                }
+            }
+
+            if let Some(clockwise) = self.clockwise(cell) {
+                if !self.is_linked(cell, clockwise) {
+                    push_arc(vec![
+                        self.point(to, inner, cell_size),
+                        self.point(to, outer, cell_size),
+                    ]);
+                }
+            }
+
+            if ring + 1 == self.rings() && cell != self.rim_opening() {
+                push_arc(self.arc(from, to, outer, cell_size));
+            }
+        }
+
+        walls
+    }
+
+    /// Moves within a ring follow the ring's arc, and moves between rings run
+    /// radially through the outer cell's angle
+    fn path(&self, cells: &[CellId], cell_size: u32) -> Vec<Vec<Point>> {
+        let mut points: Vec<Point> = cells
+            .first()
+            .map(|&cell| self.center(cell, cell_size))
+            .into_iter()
+            .collect();
+
+        for pair in cells.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            let (from_ring, _) = self.cell_coords(from);
+            let (to_ring, _) = self.cell_coords(to);
+            let from_angle = self.mid_angle(from);
+            let to_angle = self.mid_angle(to);
+
+            if from_ring == 0 || to_ring == 0 {
+                points.push(self.center(to, cell_size));
+            } else if from_ring == to_ring {
+                let radius = self.mid_radius(to, cell_size);
+                points.extend(self.turn(from_angle, to_angle, radius, cell_size));
+            } else if to_ring > from_ring {
+                // Go round to the outer cell's angle, then step out
+                let radius = self.mid_radius(from, cell_size);
+                points.extend(self.turn(from_angle, to_angle, radius, cell_size));
+                points.push(self.center(to, cell_size));
+            } else {
+                // Step in at this cell's angle, then go round
+                let radius = self.mid_radius(to, cell_size);
+                points.push(self.point(from_angle, radius, cell_size));
+                points.extend(self.turn(from_angle, to_angle, radius, cell_size));
+            }
+        }
+
+        vec![points]
+    }
+}
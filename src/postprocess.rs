@@ -0,0 +1,270 @@
+use crate::grid::{CellId, Grid};
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+/// A step run on a finished maze, such as removing dead ends or adding loops
+///
+/// Steps work on any `Grid` and draw every random choice from the RNG they
+/// are given, so a seeded run stays reproducible.
+pub trait PostProcessor {
+    /// The step as written on the command line, e.g. "braid:0.5"
+    fn spec(&self) -> String;
+
+    /// Change the maze in place, never closing off any cell in `keep`
+    fn apply(&self, grid: &mut dyn Grid, keep: &[CellId], rng: &mut ChaCha8Rng);
+}
+
+/// Open a wall at a share of the dead ends, turning them into loops
+/// A share of 1.0 removes every dead end. Where a density map sets the
+/// complexity, each dead end is opened with the share times one minus its
+/// complexity, so simple regions braid and complex ones keep their dead ends.
+#[derive(Debug, Clone, Copy)]
+pub struct Braid {
+    pub fraction: f64,
+}
+
+impl PostProcessor for Braid {
+    fn spec(&self) -> String {
+        format!("braid:{}", self.fraction)
+    }
+
+    fn apply(&self, grid: &mut dyn Grid, _keep: &[CellId], rng: &mut ChaCha8Rng) {
+        let mut dead_ends = dead_ends(grid);
+        shuffle(&mut dead_ends, rng);
+        let count = if dead_ends
+            .first()
+            .is_some_and(|&cell| grid.complexity(cell).is_some())
+        {
+            dead_ends.retain(|&cell| {
+                let local = grid.complexity(cell).unwrap_or(0.0);
+                rng.gen::<f64>() < self.fraction * (1.0 - local)
+            });
+            dead_ends.len()
+        } else {
+            (dead_ends.len() as f64 * self.fraction) as usize
+        };
+
+        for &cell in dead_ends.iter().take(count) {
+            // Opening an earlier dead end may already have cleared this one
+            if grid.links(cell).len() != 1 {
+                continue;
+            }
+            let candidates: Vec<CellId> = grid
+                .neighbors(cell)
+                .into_iter()
+                .filter(|&n| !grid.is_linked(cell, n) && !grid.links(n).is_empty())
+                .collect();
+            if !candidates.is_empty() {
+                let target = candidates[rng.gen_range(0..candidates.len())];
+                grid.link(cell, target);
+            }
+        }
+    }
+}
+
+/// Wall off a share of the dead-end cells, shortening each dead end by one cell
+#[derive(Debug, Clone, Copy)]
+pub struct Cull {
+    pub fraction: f64,
+}
+
+impl PostProcessor for Cull {
+    fn spec(&self) -> String {
+        format!("cull:{}", self.fraction)
+    }
+
+    fn apply(&self, grid: &mut dyn Grid, keep: &[CellId], rng: &mut ChaCha8Rng) {
+        let mut dead_ends = dead_ends(grid);
+        dead_ends.retain(|cell| !keep.contains(cell));
+        shuffle(&mut dead_ends, rng);
+        let count = (dead_ends.len() as f64 * self.fraction) as usize;
+
+        for &cell in dead_ends.iter().take(count) {
+            cull(grid, cell);
+        }
+    }
+}
+
+/// Keep culling dead ends until a share of the cells has been walled off
+#[derive(Debug, Clone, Copy)]
+pub struct Sparsify {
+    pub fraction: f64,
+}
+
+impl PostProcessor for Sparsify {
+    fn spec(&self) -> String {
+        format!("sparsify:{}", self.fraction)
+    }
+
+    fn apply(&self, grid: &mut dyn Grid, keep: &[CellId], rng: &mut ChaCha8Rng) {
+        let target = (grid.active_count() as f64 * self.fraction) as usize;
+        let mut removed = 0;
+
+        while removed < target {
+            let mut dead_ends = dead_ends(grid);
+            dead_ends.retain(|cell| !keep.contains(cell));
+            if dead_ends.is_empty() {
+                break;
+            }
+            shuffle(&mut dead_ends, rng);
+            for cell in dead_ends {
+                if removed >= target {
+                    break;
+                }
+                if cull(grid, cell) {
+                    removed += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Open walls between a number of random pairs of neighbouring cells
+#[derive(Debug, Clone, Copy)]
+pub struct AddLoops {
+    pub count: usize,
+}
+
+impl PostProcessor for AddLoops {
+    fn spec(&self) -> String {
+        format!("add-loops:{}", self.count)
+    }
+
+    fn apply(&self, grid: &mut dyn Grid, _keep: &[CellId], rng: &mut ChaCha8Rng) {
+        // Walls between two cells still in the maze, each once
+        let mut walls = Vec::new();
+        for cell in grid.cells() {
+            if grid.links(cell).is_empty() {
+                continue;
+            }
+            for neighbor in grid.neighbors(cell) {
+                if neighbor > cell
+                    && !grid.is_linked(cell, neighbor)
+                    && !grid.links(neighbor).is_empty()
+                {
+                    walls.push((cell, neighbor));
+                }
+            }
+        }
+
+        shuffle(&mut walls, rng);
+        for &(a, b) in walls.iter().take(self.count) {
+            grid.link(a, b);
+        }
+    }
+}
+
+/// Post-processing steps run in order after generation
+#[derive(Default)]
+pub struct Pipeline {
+    steps: Vec<Box<dyn PostProcessor>>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Pipeline::default()
+    }
+
+    /// Build a pipeline from specs such as "braid:0.5", "sparsify:0.2" or "add-loops:30"
+    pub fn parse<S: AsRef<str>>(specs: &[S]) -> Result<Self, String> {
+        let mut pipeline = Pipeline::new();
+        for spec in specs {
+            pipeline.push(parse_step(spec.as_ref())?);
+        }
+        Ok(pipeline)
+    }
+
+    /// Add a step to the end of the pipeline
+    pub fn push(&mut self, step: Box<dyn PostProcessor>) {
+        self.steps.push(step);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// The steps as written on the command line
+    pub fn specs(&self) -> Vec<String> {
+        self.steps.iter().map(|step| step.spec()).collect()
+    }
+
+    /// Run every step in order
+    pub fn apply(&self, grid: &mut dyn Grid, keep: &[CellId], rng: &mut ChaCha8Rng) {
+        for step in &self.steps {
+            step.apply(grid, keep, rng);
+        }
+    }
+}
+
+/// Parse one step, written as "name" or "name:value"
+/// Braid and cull default to every dead end; sparsify and add-loops need a value.
+pub fn parse_step(spec: &str) -> Result<Box<dyn PostProcessor>, String> {
+    let (name, value) = match spec.split_once(':') {
+        Some((name, value)) => (name.trim(), Some(value.trim())),
+        None => (spec.trim(), None),
+    };
+
+    let fraction = |default: Option<f64>| -> Result<f64, String> {
+        let fraction = match value {
+            Some(value) => value
+                .parse::<f64>()
+                .map_err(|e| format!("Invalid value in post step {}: {}", spec, e))?,
+            None => default.ok_or_else(|| format!("Post step {} needs a value", name))?,
+        };
+        if (0.0..=1.0).contains(&fraction) {
+            Ok(fraction)
+        } else {
+            Err(format!("Post step {} needs a value between 0.0 and 1.0", spec))
+        }
+    };
+
+    match name.to_lowercase().replace('_', "-").as_str() {
+        "braid" => Ok(Box::new(Braid {
+            fraction: fraction(Some(1.0))?,
+        })),
+        "cull" => Ok(Box::new(Cull {
+            fraction: fraction(Some(1.0))?,
+        })),
+        "sparsify" => Ok(Box::new(Sparsify {
+            fraction: fraction(None)?,
+        })),
+        "add-loops" | "loops" => {
+            let count = value
+                .ok_or_else(|| format!("Post step {} needs a count", name))?
+                .parse()
+                .map_err(|e| format!("Invalid count in post step {}: {}", spec, e))?;
+            Ok(Box::new(AddLoops { count }))
+        }
+        _ => Err(format!(
+            "Unknown post step: {} (known: braid, cull, sparsify, add-loops)",
+            name
+        )),
+    }
+}
+
+/// Cells with exactly one open passage
+fn dead_ends(grid: &dyn Grid) -> Vec<CellId> {
+    grid.cells()
+        .into_iter()
+        .filter(|&cell| grid.links(cell).len() == 1)
+        .collect()
+}
+
+/// Close a dead end's only passage, if it is still a dead end
+fn cull(grid: &mut dyn Grid, cell: CellId) -> bool {
+    match grid.links(cell).as_slice() {
+        &[only] => {
+            grid.unlink(cell, only);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Fisher-Yates shuffle driven by the maze's RNG
+fn shuffle<T>(items: &mut [T], rng: &mut ChaCha8Rng) {
+    for i in 0..items.len() {
+        let j = rng.gen_range(i..items.len());
+        items.swap(i, j);
+    }
+}
@@ -1,4 +1,5 @@
-use crate::maze::Maze;
+use crate::grid::{CellId, Tiling};
+use crate::maze::{Maze, Side};
 use image::{DynamicImage, ImageBuffer, Rgb, RgbImage};
 use std::io::Cursor;
 
@@ -7,11 +8,7 @@ use std::io::Cursor;
 /// Returns (r, g, b) as u8 values
 fn parse_hex_color(hex: &str) -> Result<[u8; 3], String> {
     let hex = hex.trim();
-    let hex = if hex.starts_with('#') {
-        &hex[1..]
-    } else {
-        hex
-    };
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
 
     if hex.len() != 6 {
         return Err(format!("Hex color must be 6 digits, got: {}", hex));
@@ -27,8 +24,26 @@ fn parse_hex_color(hex: &str) -> Result<[u8; 3], String> {
     Ok([r, g, b])
 }
 
+/// Colour of the gaps where a wrapped maze continues on the opposite edge
+const SEAM_COLOR: Rgb<u8> = Rgb([0, 128, 255]);
+
 /// Render a maze to a PNG image
+/// Passages across the seam of a wrapped maze are marked on the border.
 pub fn render_maze(maze: &Maze, cell_size: u32) -> Result<RgbImage, String> {
+    let mut img = render_walls(maze, cell_size);
+    mark_seams(&mut img, maze, cell_size);
+    Ok(img)
+}
+
+/// Render a wrapped maze as a grid of copies, so the seamless structure shows
+/// `copies` tiles are placed along each wrapped axis.
+pub fn render_maze_tiled(maze: &Maze, cell_size: u32, copies: u32) -> Result<RgbImage, String> {
+    let img = render_walls(maze, cell_size);
+    Ok(tile(&img, maze, cell_size, copies))
+}
+
+/// Draw the walls of a maze and open its entrance and exit
+fn render_walls(maze: &Maze, cell_size: u32) -> RgbImage {
     let img_width = maze.width() * cell_size + 1;
     let img_height = maze.height() * cell_size + 1;
 
@@ -39,9 +54,12 @@ pub fn render_maze(maze: &Maze, cell_size: u32) -> Result<RgbImage, String> {
         *pixel = Rgb([255, 255, 255]);
     }
 
-    // Draw walls
+    // Draw walls (masked-off cells are left blank)
     for y in 0..maze.height() {
         for x in 0..maze.width() {
+            if !maze.is_active(x, y) {
+                continue;
+            }
             if let Some(cell) = maze.get_cell(x, y) {
                 let px = x * cell_size;
                 let py = y * cell_size;
@@ -95,41 +113,73 @@ pub fn render_maze(maze: &Maze, cell_size: u32) -> Result<RgbImage, String> {
         }
     }
 
-    // Mark entry (top-left) - remove north and west walls
-    if maze.get_cell(0, 0).is_some() {
-        // Remove entry walls visually (make opening)
-        for i in 1..cell_size {
-            if i < img_width {
-                *img.get_pixel_mut(i, 0) = Rgb([255, 255, 255]);
-            }
-            if i < img_height {
-                *img.get_pixel_mut(0, i) = Rgb([255, 255, 255]);
-            }
+    // Open the border walls at the entrance and exit
+    for endpoint in [maze.start(), maze.goal()] {
+        if let Some(side) = endpoint.side {
+            paint_wall(&mut img, endpoint.x, endpoint.y, side, cell_size, Rgb([255, 255, 255]));
         }
     }
 
-    // Mark exit (bottom-right) - remove south and east walls
-    let exit_x = maze.width() - 1;
-    let exit_y = maze.height() - 1;
-    if maze.get_cell(exit_x, exit_y).is_some() {
-        let px = exit_x * cell_size;
-        let py = exit_y * cell_size;
-        // Remove exit walls visually (make opening)
-        let sx = px + cell_size;
-        let sy = py + cell_size;
-        for i in 1..cell_size {
-            let x_pos = sx.saturating_sub(i);
-            let y_pos = sy.saturating_sub(i);
-            if x_pos < img_width && sy < img_height {
-                *img.get_pixel_mut(x_pos, sy) = Rgb([255, 255, 255]);
-            }
-            if sx < img_width && y_pos < img_height {
-                *img.get_pixel_mut(sx, y_pos) = Rgb([255, 255, 255]);
+    img
+}
+
+/// Colour the open border walls that lead across the seam of a wrapped maze
+fn mark_seams(img: &mut RgbImage, maze: &Maze, cell_size: u32) {
+    for (x, y) in maze.active_cells() {
+        let Some(cell) = maze.get_cell(x, y) else {
+            continue;
+        };
+        let open = [
+            (Side::North, !cell.north),
+            (Side::South, !cell.south),
+            (Side::East, !cell.east),
+            (Side::West, !cell.west),
+        ];
+        for (side, is_open) in open {
+            if is_open && maze.crosses_seam(x, y, side) {
+                paint_wall(img, x, y, side, cell_size, SEAM_COLOR);
             }
         }
     }
+}
 
-    Ok(img)
+/// Repeat a rendered maze along its wrapped axes, sharing the border pixels
+fn tile(img: &RgbImage, maze: &Maze, cell_size: u32, copies: u32) -> RgbImage {
+    let columns = if maze.wrap().wraps_x() { copies.max(1) } else { 1 };
+    let rows = if maze.wrap().wraps_y() { copies.max(1) } else { 1 };
+    let step_x = maze.width() * cell_size;
+    let step_y = maze.height() * cell_size;
+
+    let mut tiled: RgbImage = ImageBuffer::new(columns * step_x + 1, rows * step_y + 1);
+    for row in 0..rows {
+        for column in 0..columns {
+            image::imageops::replace(
+                &mut tiled,
+                img,
+                (column * step_x) as i64,
+                (row * step_y) as i64,
+            );
+        }
+    }
+    tiled
+}
+
+/// Paint one wall of a cell, keeping the corner pixels so neighbouring walls stay joined
+fn paint_wall(img: &mut RgbImage, x: u32, y: u32, side: Side, cell_size: u32, color: Rgb<u8>) {
+    let px = x * cell_size;
+    let py = y * cell_size;
+
+    for i in 1..cell_size {
+        let (wx, wy) = match side {
+            Side::North => (px + i, py),
+            Side::South => (px + i, py + cell_size),
+            Side::West => (px, py + i),
+            Side::East => (px + cell_size, py + i),
+        };
+        if wx < img.width() && wy < img.height() {
+            *img.get_pixel_mut(wx, wy) = color;
+        }
+    }
 }
 
 /// Draw a line between two points using Bresenham's line algorithm
@@ -194,9 +244,35 @@ pub fn render_maze_with_solution(
     line_thickness: f32,
 ) -> Result<RgbImage, String> {
     let mut img = render_maze(maze, cell_size)?;
+    draw_solution(&mut img, cell_size, solution, line_color, line_thickness)?;
+    Ok(img)
+}
+
+/// Render a wrapped maze with its solution as a grid of copies
+pub fn render_maze_with_solution_tiled(
+    maze: &Maze,
+    cell_size: u32,
+    copies: u32,
+    solution: &[(u32, u32)],
+    line_color: &str,
+    line_thickness: f32,
+) -> Result<RgbImage, String> {
+    let mut img = render_walls(maze, cell_size);
+    draw_solution(&mut img, cell_size, solution, line_color, line_thickness)?;
+    Ok(tile(&img, maze, cell_size, copies))
+}
 
+/// Draw the solution path through the cell centres
+/// Steps across the seam of a wrapped maze run out to one edge and back in from the other.
+fn draw_solution(
+    img: &mut RgbImage,
+    cell_size: u32,
+    solution: &[(u32, u32)],
+    line_color: &str,
+    line_thickness: f32,
+) -> Result<(), String> {
     if solution.is_empty() {
-        return Ok(img);
+        return Ok(());
     }
 
     // Parse hex color
@@ -218,15 +294,108 @@ pub fn render_maze_with_solution(
         .collect();
 
     // Draw lines connecting consecutive points
+    let half = (cell_size / 2) as i32;
     for i in 0..(pixel_coords.len() - 1) {
         let (x0, y0) = pixel_coords[i];
         let (x1, y1) = pixel_coords[i + 1];
-        draw_line(&mut img, x0, y0, x1, y1, color, thickness);
+
+        // Neighbours more than a cell apart are joined across the seam
+        let dx = solution[i + 1].0 as i32 - solution[i].0 as i32;
+        let dy = solution[i + 1].1 as i32 - solution[i].1 as i32;
+        if dx.abs() > 1 || dy.abs() > 1 {
+            let (sx, sy) = (-dx.signum() * half, -dy.signum() * half);
+            draw_line(img, x0, y0, x0 + sx, y0 + sy, color, thickness);
+            draw_line(img, x1 - sx, y1 - sy, x1, y1, color, thickness);
+        } else {
+            draw_line(img, x0, y0, x1, y1, color, thickness);
+        }
+    }
+
+    Ok(())
+}
+
+/// Render a non-square maze to a PNG image by drawing its wall segments
+pub fn render_tiling<T: Tiling + ?Sized>(maze: &T, cell_size: u32) -> Result<RgbImage, String> {
+    let (img_width, img_height) = maze.canvas_size(cell_size);
+    let mut img: RgbImage = ImageBuffer::from_pixel(img_width, img_height, Rgb([255, 255, 255]));
+
+    let mut lines = maze.walls(cell_size);
+    lines.extend(maze.markers(cell_size));
+    for ((x0, y0), (x1, y1)) in lines {
+        draw_line(
+            &mut img,
+            x0.round() as i32,
+            y0.round() as i32,
+            x1.round() as i32,
+            y1.round() as i32,
+            Rgb([0, 0, 0]),
+            1.0,
+        );
     }
 
     Ok(img)
 }
 
+/// Render a non-square maze with the solution path highlighted as a line
+pub fn render_tiling_with_solution<T: Tiling + ?Sized>(
+    maze: &T,
+    cell_size: u32,
+    solution: &[CellId],
+    line_color: &str,
+    line_thickness: f32,
+) -> Result<RgbImage, String> {
+    let mut img = render_tiling(maze, cell_size)?;
+
+    let rgb = parse_hex_color(line_color)?;
+    let color = Rgb([rgb[0], rgb[1], rgb[2]]);
+    let thickness = line_thickness * cell_size as f32;
+
+    for line in maze.path(solution, cell_size) {
+        let points: Vec<(i32, i32)> = line
+            .into_iter()
+            .map(|(x, y)| (x.round() as i32, y.round() as i32))
+            .collect();
+
+        // A line through a single cell is drawn as a dot
+        if let [(x, y)] = points[..] {
+            draw_line(&mut img, x, y, x, y, color, thickness);
+        }
+        for pair in points.windows(2) {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            draw_line(&mut img, x0, y0, x1, y1, color, thickness);
+        }
+    }
+
+    Ok(img)
+}
+
+/// Save a non-square maze to a PNG file
+pub fn save_tiling<T: Tiling + ?Sized>(
+    maze: &T,
+    cell_size: u32,
+    output_path: &str,
+) -> Result<(), String> {
+    let img = render_tiling(maze, cell_size)?;
+    img.save(output_path)
+        .map_err(|e| format!("Failed to save image: {}", e))?;
+    Ok(())
+}
+
+/// Save a non-square maze with solution to a PNG file
+pub fn save_tiling_with_solution<T: Tiling + ?Sized>(
+    maze: &T,
+    cell_size: u32,
+    solution: &[CellId],
+    output_path: &str,
+    line_color: &str,
+    line_thickness: f32,
+) -> Result<(), String> {
+    let img = render_tiling_with_solution(maze, cell_size, solution, line_color, line_thickness)?;
+    img.save(output_path)
+        .map_err(|e| format!("Failed to save image: {}", e))?;
+    Ok(())
+}
+
 /// Save a maze to a PNG file
 pub fn save_maze(maze: &Maze, cell_size: u32, output_path: &str) -> Result<(), String> {
     let img = render_maze(maze, cell_size)?;
@@ -250,6 +419,42 @@ pub fn save_maze_with_solution(
     Ok(())
 }
 
+/// Save a wrapped maze as a grid of copies to a PNG file
+pub fn save_maze_tiled(
+    maze: &Maze,
+    cell_size: u32,
+    copies: u32,
+    output_path: &str,
+) -> Result<(), String> {
+    let img = render_maze_tiled(maze, cell_size, copies)?;
+    img.save(output_path)
+        .map_err(|e| format!("Failed to save image: {}", e))?;
+    Ok(())
+}
+
+/// Save a wrapped maze with solution as a grid of copies to a PNG file
+pub fn save_maze_with_solution_tiled(
+    maze: &Maze,
+    cell_size: u32,
+    copies: u32,
+    solution: &[(u32, u32)],
+    output_path: &str,
+    line_color: &str,
+    line_thickness: f32,
+) -> Result<(), String> {
+    let img = render_maze_with_solution_tiled(
+        maze,
+        cell_size,
+        copies,
+        solution,
+        line_color,
+        line_thickness,
+    )?;
+    img.save(output_path)
+        .map_err(|e| format!("Failed to save image: {}", e))?;
+    Ok(())
+}
+
 /// Render a maze to PNG bytes
 pub fn render_maze_to_bytes(maze: &Maze, cell_size: u32) -> Result<Vec<u8>, String> {
     let img = render_maze(maze, cell_size)?;
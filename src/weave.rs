@@ -0,0 +1,325 @@
+use crate::bitset::BitSet;
+use crate::grid::{Axis, CellId, Grid, Point, Tiling};
+
+/// Passage bits stored per cell
+const EAST: usize = 0;
+const SOUTH: usize = 1;
+const BITS_PER_CELL: usize = 2;
+
+/// Directions as (dx, dy), in the order west, east, north, south
+const DIRECTIONS: [(i64, i64); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+/// A square-grid maze where passages can tunnel under perpendicular corridors
+///
+/// A crossing cell carries a straight corridor over a tunnel that joins the
+/// two cells on either side of it. Tunnels are offered to generators as extra
+/// neighbours two cells away, so tree generators produce crossings while the
+/// maze stays a tree. Generators that link a cell as soon as they pick it
+/// (recursive backtracking, hunt-and-kill, growing tree, Prim, Aldous-Broder)
+/// produce weaves; Kruskal's edge list is fixed up front, so it produces none.
+#[derive(Clone, Debug)]
+pub struct WeaveMaze {
+    width: u32,
+    height: u32,
+    passages: BitSet,
+    crossings: BitSet,
+    vertical_tunnels: BitSet,
+}
+
+impl WeaveMaze {
+    /// Create a new weave maze with all walls present and no crossings
+    pub fn new(width: u32, height: u32) -> Self {
+        let cells = width as usize * height as usize;
+        WeaveMaze {
+            width,
+            height,
+            passages: BitSet::new(cells * BITS_PER_CELL, false),
+            crossings: BitSet::new(cells, false),
+            vertical_tunnels: BitSet::new(cells, false),
+        }
+    }
+
+    /// Get the width of the maze in cells
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get the height of the maze in cells
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Convert (x, y) to a cell id
+    pub fn cell_id(&self, x: u32, y: u32) -> CellId {
+        y as usize * self.width as usize + x as usize
+    }
+
+    /// Convert a cell id back to (x, y)
+    pub fn cell_coords(&self, cell: CellId) -> (u32, u32) {
+        let width = self.width as usize;
+        ((cell % width) as u32, (cell / width) as u32)
+    }
+
+    /// Whether a tunnel passes under a cell
+    pub fn is_crossing(&self, cell: CellId) -> bool {
+        self.crossings.get(cell)
+    }
+
+    /// Whether the tunnel under a crossing runs north-south
+    fn has_vertical_tunnel(&self, cell: CellId) -> bool {
+        self.vertical_tunnels.get(cell)
+    }
+
+    /// Whether a direction index runs north-south
+    fn is_vertical(direction: usize) -> bool {
+        direction >= 2
+    }
+
+    /// The cell one step away in a direction, if it is inside the maze
+    fn step(&self, cell: CellId, direction: usize) -> Option<CellId> {
+        let (x, y) = self.cell_coords(cell);
+        let (dx, dy) = DIRECTIONS[direction];
+        let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+        if nx < 0 || ny < 0 || nx >= self.width as i64 || ny >= self.height as i64 {
+            None
+        } else {
+            Some(self.cell_id(nx as u32, ny as u32))
+        }
+    }
+
+    /// Bit holding the passage between two side-by-side cells
+    fn passage_bit(&self, a: CellId, b: CellId) -> Option<usize> {
+        let (low, high) = (a.min(b), a.max(b));
+        if self.step(low, 1) == Some(high) {
+            Some(low * BITS_PER_CELL + EAST)
+        } else if self.step(low, 3) == Some(high) {
+            Some(low * BITS_PER_CELL + SOUTH)
+        } else {
+            None
+        }
+    }
+
+    /// Direction from one cell to another that is `distance` steps away in a straight line
+    fn direction_between(&self, from: CellId, to: CellId, distance: u32) -> Option<usize> {
+        (0..DIRECTIONS.len()).find(|&d| {
+            let mut cell = Some(from);
+            for _ in 0..distance {
+                cell = cell.and_then(|c| self.step(c, d));
+            }
+            cell == Some(to)
+        })
+    }
+
+    /// Whether the tunnel under a neighbouring cell continues from this side
+    fn tunnels_under(&self, middle: CellId, direction: usize) -> bool {
+        self.is_crossing(middle) && self.has_vertical_tunnel(middle) == Self::is_vertical(direction)
+    }
+
+    /// Whether a cell opens towards a direction, through a passage or a tunnel
+    fn is_open(&self, cell: CellId, direction: usize) -> bool {
+        match self.step(cell, direction) {
+            Some(next) => {
+                self.passage_bit(cell, next)
+                    .is_some_and(|bit| self.passages.get(bit))
+                    || self.tunnels_under(next, direction)
+            }
+            None => false,
+        }
+    }
+
+    /// Whether a tunnel could be dug under `middle` in a direction: the cell
+    /// must be a straight corridor running across that direction
+    fn can_tunnel_under(&self, middle: CellId, direction: usize) -> bool {
+        if self.is_crossing(middle) {
+            return false;
+        }
+        let across = if Self::is_vertical(direction) { [0, 1] } else { [2, 3] };
+        let along = if Self::is_vertical(direction) { [2, 3] } else { [0, 1] };
+        across.iter().all(|&d| self.is_open(middle, d))
+            && along.iter().all(|&d| !self.is_open(middle, d))
+            && self.step(middle, direction).is_some()
+    }
+}
+
+impl Grid for WeaveMaze {
+    fn cell_count(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    /// Adjacent cells, plus the cell beyond any corridor this cell could tunnel under
+    fn neighbors(&self, cell: CellId) -> Vec<CellId> {
+        let mut neighbors = Vec::new();
+
+        for direction in 0..DIRECTIONS.len() {
+            // The sides of a crossing belong to its tunnel
+            if self.is_crossing(cell) && self.tunnels_under(cell, direction) {
+                continue;
+            }
+            let Some(next) = self.step(cell, direction) else {
+                continue;
+            };
+
+            if self.tunnels_under(next, direction) {
+                neighbors.extend(self.step(next, direction));
+            } else {
+                neighbors.push(next);
+                if self.can_tunnel_under(next, direction) {
+                    neighbors.extend(self.step(next, direction));
+                }
+            }
+        }
+
+        neighbors
+    }
+
+    fn link(&mut self, a: CellId, b: CellId) {
+        if let Some(bit) = self.passage_bit(a, b) {
+            let direction = self.direction_between(a, b, 1).unwrap_or(0);
+            // Opening a crossing's side would cut into its tunnel
+            if !self.tunnels_under(a, direction) && !self.tunnels_under(b, direction) {
+                self.passages.set(bit, true);
+            }
+        } else if let Some(direction) = self.direction_between(a, b, 2) {
+            if let Some(middle) = self.step(a, direction) {
+                if self.can_tunnel_under(middle, direction) {
+                    self.crossings.set(middle, true);
+                    self.vertical_tunnels.set(middle, Self::is_vertical(direction));
+                }
+            }
+        }
+    }
+
+    fn unlink(&mut self, a: CellId, b: CellId) {
+        if let Some(bit) = self.passage_bit(a, b) {
+            self.passages.set(bit, false);
+        } else if let Some(direction) = self.direction_between(a, b, 2) {
+            if let Some(middle) = self.step(a, direction) {
+                if self.tunnels_under(middle, direction) {
+                    self.crossings.set(middle, false);
+                }
+            }
+        }
+    }
+
+    fn is_linked(&self, a: CellId, b: CellId) -> bool {
+        if let Some(bit) = self.passage_bit(a, b) {
+            return self.passages.get(bit);
+        }
+        self.direction_between(a, b, 2)
+            .and_then(|direction| {
+                self.step(a, direction)
+                    .map(|middle| self.tunnels_under(middle, direction))
+            })
+            .unwrap_or(false)
+    }
+
+    fn is_boundary(&self, cell: CellId) -> bool {
+        (0..DIRECTIONS.len()).any(|direction| self.step(cell, direction).is_none())
+    }
+
+    fn axis(&self, a: CellId, b: CellId) -> Option<Axis> {
+        let (ax, ay) = self.cell_coords(a);
+        let (bx, by) = self.cell_coords(b);
+        if ay == by {
+            Some(Axis::Horizontal)
+        } else if ax == bx {
+            Some(Axis::Vertical)
+        } else {
+            None
+        }
+    }
+
+    fn position(&self, cell: CellId) -> Option<(f64, f64)> {
+        let (x, y) = self.cell_coords(cell);
+        Some((
+            (x as f64 + 0.5) / self.width() as f64,
+            (y as f64 + 0.5) / self.height() as f64,
+        ))
+    }
+}
+
+impl Tiling for WeaveMaze {
+    fn name(&self) -> &'static str {
+        "weave"
+    }
+
+    fn start(&self) -> CellId {
+        0
+    }
+
+    fn goal(&self) -> CellId {
+        self.cell_count().saturating_sub(1)
+    }
+
+    fn canvas_size(&self, cell_size: u32) -> (u32, u32) {
+        (self.width * cell_size + 1, self.height * cell_size + 1)
+    }
+
+    fn center(&self, cell: CellId, cell_size: u32) -> Point {
+        let (x, y) = self.cell_coords(cell);
+        let half = cell_size as f64 / 2.0;
+        (
+            (x * cell_size) as f64 + half,
+            (y * cell_size) as f64 + half,
+        )
+    }
+
+    /// Cells are drawn inset from their edges so corridors have visible
+    /// sides; a crossing's corridor runs straight through, and its tunnel
+    /// shows as short wall stubs either side
+    fn walls(&self, cell_size: u32) -> Vec<(Point, Point)> {
+        let mut walls = Vec::new();
+        let inset = (cell_size as f64 / 4.0).round();
+
+        for cell in 0..self.cell_count() {
+            let (x, y) = self.cell_coords(cell);
+            let (x0, y0) = ((x * cell_size) as f64, (y * cell_size) as f64);
+            let (x1, y1) = (x0 + cell_size as f64, y0 + cell_size as f64);
+            let (xi0, yi0, xi1, yi1) = (x0 + inset, y0 + inset, x1 - inset, y1 - inset);
+
+            if self.is_crossing(cell) {
+                if self.has_vertical_tunnel(cell) {
+                    walls.push(((x0, yi0), (x1, yi0)));
+                    walls.push(((x0, yi1), (x1, yi1)));
+                    walls.push(((xi0, y0), (xi0, yi0)));
+                    walls.push(((xi1, y0), (xi1, yi0)));
+                    walls.push(((xi0, yi1), (xi0, y1)));
+                    walls.push(((xi1, yi1), (xi1, y1)));
+                } else {
+                    walls.push(((xi0, y0), (xi0, y1)));
+                    walls.push(((xi1, y0), (xi1, y1)));
+                    walls.push(((x0, yi0), (xi0, yi0)));
+                    walls.push(((x0, yi1), (xi0, yi1)));
+                    walls.push(((xi1, yi0), (x1, yi0)));
+                    walls.push(((xi1, yi1), (x1, yi1)));
+                }
+                continue;
+            }
+
+            let open = |direction: usize| {
+                self.is_open(cell, direction)
+                    || (cell == self.start() && direction == 2)
+                    || (cell == self.goal() && direction == 3)
+            };
+
+            // West, east, north, south: a closed side is one inset wall,
+            // an open side is the two walls of a corridor leading out
+            let sides = [
+                ((xi0, yi0), (xi0, yi1), (x0, yi0), (x0, yi1)),
+                ((xi1, yi0), (xi1, yi1), (x1, yi0), (x1, yi1)),
+                ((xi0, yi0), (xi1, yi0), (xi0, y0), (xi1, y0)),
+                ((xi0, yi1), (xi1, yi1), (xi0, y1), (xi1, y1)),
+            ];
+            for (direction, (a, b, outer_a, outer_b)) in sides.into_iter().enumerate() {
+                if open(direction) {
+                    walls.push((a, outer_a));
+                    walls.push((b, outer_b));
+                } else {
+                    walls.push((a, b));
+                }
+            }
+        }
+
+        walls
+    }
+}
@@ -0,0 +1,109 @@
+//! Regression checks for composite mazes stitched from several generators
+
+use maze_generator::algorithms::composite::{Composite, Layout, Region};
+use maze_generator::algorithms::{
+    BinaryTree, CellularAutomata, Dungeon, MazeGenerator, RecursiveBacktracking, Wilsons,
+};
+use maze_generator::events::GenerationEvent;
+use maze_generator::grid::{CellId, Grid};
+use maze_generator::mask::Mask;
+use maze_generator::maze::Maze;
+
+/// Open passages of a maze, cell by cell
+fn passages(maze: &Maze) -> Vec<Vec<CellId>> {
+    maze.cells()
+        .into_iter()
+        .map(|cell| maze.links(cell))
+        .collect()
+}
+
+#[test]
+fn single_region_keeps_dungeon_trim() {
+    // Connecting each region used to reopen the dead ends a dungeon walls off
+    let dungeon = Dungeon::default();
+    let composite = Composite::new(Layout::Bands, vec![Region::new(&dungeon)]);
+    for seed in 0..10 {
+        let alone = dungeon.generate(30, 20, 0.5, Some(seed)).unwrap().maze;
+        let stitched = composite.generate(30, 20, 0.5, Some(seed)).unwrap().maze;
+        assert_eq!(passages(&stitched), passages(&alone), "seed {}", seed);
+    }
+}
+
+#[test]
+fn events_replay_to_the_same_maze() {
+    let (dungeon, wilsons) = (Dungeon::default(), Wilsons::default());
+    let composite = Composite::new(
+        Layout::Columns,
+        vec![
+            Region::new(&dungeon),
+            Region::new(&RecursiveBacktracking),
+            Region::new(&wilsons),
+        ],
+    );
+    for seed in 0..5 {
+        let (generated, events) = composite
+            .generate_with_events(30, 20, 0.5, Some(seed))
+            .unwrap();
+        // The regions' own steps come through, not just the passages they left
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, GenerationEvent::Visit(_))));
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, GenerationEvent::AddWall(..))));
+
+        let mut replayed = Maze::new(30, 20);
+        for event in events {
+            event.apply(&mut replayed);
+        }
+        assert_eq!(
+            passages(&replayed),
+            passages(&generated.maze),
+            "seed {}",
+            seed
+        );
+    }
+}
+
+#[test]
+fn composite_mazes_are_solvable() {
+    let (dungeon, wilsons) = (Dungeon::default(), Wilsons::default());
+    let cells = CellularAutomata::default();
+    let mixed: [&dyn MazeGenerator; 4] = [&dungeon, &cells, &BinaryTree, &wilsons];
+
+    for layout in [Layout::Quadrants, Layout::Bands, Layout::Columns] {
+        let regions = mixed
+            .iter()
+            .map(|&generator| Region::new(generator))
+            .collect();
+        let mut composite = Composite::new(layout, regions);
+        composite.passages = 2;
+        for seed in 0..10 {
+            let maze = composite.generate(31, 23, 0.5, Some(seed)).unwrap().maze;
+            assert!(
+                maze.solve().is_some(),
+                "{} seed {} is unsolvable",
+                layout,
+                seed
+            );
+        }
+    }
+
+    // Zones cut the regions into shapes that are not rectangles
+    let mut zone = Mask::new(31, 23);
+    for y in 0..23 {
+        for x in 0..31 {
+            zone.set_active(x, y, x + y < 27);
+        }
+    }
+    let mut diagonal = Region::new(&dungeon);
+    diagonal.zone = Some(zone);
+    let composite = Composite::new(
+        Layout::Zones,
+        vec![diagonal, Region::new(&RecursiveBacktracking)],
+    );
+    for seed in 0..10 {
+        let maze = composite.generate(31, 23, 0.5, Some(seed)).unwrap().maze;
+        assert!(maze.solve().is_some(), "zones seed {} is unsolvable", seed);
+    }
+}
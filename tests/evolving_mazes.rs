@@ -0,0 +1,61 @@
+//! Checks that an evolving maze stays perfect as its origin keeps shifting
+
+use maze_generator::algorithms::origin_shift::EvolvingMaze;
+use maze_generator::algorithms::{seeded_rng, MazeGenerator, Wilsons};
+use maze_generator::grid::{CellId, Grid};
+use maze_generator::maze::Maze;
+
+/// Number of passages, and of cells reachable from the first cell
+fn passages_and_reach(maze: &Maze) -> (usize, usize) {
+    let cells = maze.cells();
+    let passages = cells
+        .iter()
+        .map(|&cell| maze.links(cell).len())
+        .sum::<usize>()
+        / 2;
+
+    let mut seen = vec![false; maze.cell_count()];
+    let mut stack: Vec<CellId> = vec![cells[0]];
+    seen[cells[0]] = true;
+    let mut reached = 0;
+    while let Some(cell) = stack.pop() {
+        reached += 1;
+        for next in maze.links(cell) {
+            if !seen[next] {
+                seen[next] = true;
+                stack.push(next);
+            }
+        }
+    }
+    (passages, reached)
+}
+
+#[test]
+fn shift_keeps_the_maze_perfect() {
+    for seed in 0..5 {
+        let maze = Wilsons::default()
+            .generate(12, 9, 0.5, Some(seed))
+            .unwrap()
+            .maze;
+        let cells = maze.active_count();
+        let mut evolving = EvolvingMaze::new(maze).unwrap();
+        let mut rng = seeded_rng(Some(seed));
+
+        for step in 0..500 {
+            if let Some(shift) = evolving.shift(&mut rng) {
+                let maze = evolving.maze();
+                assert!(maze.is_linked(shift.opened.0, shift.opened.1));
+                assert!(!maze.is_linked(shift.closed.0, shift.closed.1));
+            }
+            let (passages, reached) = passages_and_reach(evolving.maze());
+            assert_eq!(
+                passages,
+                cells - 1,
+                "seed {} step {} made a loop",
+                seed,
+                step
+            );
+            assert_eq!(reached, cells, "seed {} step {} cut off cells", seed, step);
+        }
+    }
+}
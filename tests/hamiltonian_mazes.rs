@@ -0,0 +1,46 @@
+//! Checks that Hamiltonian mazes solve through every cell
+
+use maze_generator::algorithms::{seeded_rng, Registry};
+use maze_generator::maze::{Endpoint, Maze, Side};
+
+fn assert_visits_every_cell(maze: &Maze, label: &str) {
+    let solution = maze.solve().unwrap_or_else(|| panic!("{}: no solution", label));
+    let cells = (maze.width() * maze.height()) as usize;
+    assert_eq!(solution.len(), cells, "{}: solution skips cells", label);
+}
+
+#[test]
+fn solution_visits_every_cell() {
+    let registry = Registry::builtin();
+    let generator = registry.get("hamiltonian").expect("built-in generator");
+    for &(width, height) in &[(6, 6), (7, 7), (5, 8), (8, 5), (3, 3), (1, 6), (9, 1)] {
+        for seed in 0..10 {
+            let maze = generator
+                .generate(width, height, 0.5, Some(seed))
+                .expect("valid size")
+                .maze;
+            assert_visits_every_cell(&maze, &format!("{}x{} seed {}", width, height, seed));
+        }
+    }
+}
+
+#[test]
+fn odd_grid_starts_from_any_corner() {
+    let registry = Registry::builtin();
+    let generator = registry.get("hamiltonian").expect("built-in generator");
+    let starts = [
+        Endpoint::new(0, 0, Some(Side::North)),
+        Endpoint::new(6, 0, Some(Side::East)),
+        Endpoint::new(0, 4, Some(Side::West)),
+        Endpoint::new(6, 4, Some(Side::South)),
+    ];
+    for start in starts {
+        for seed in 0..5 {
+            let mut maze = Maze::new(7, 5);
+            maze.set_start(start);
+            generator.carve(&mut maze, 0.5, &mut seeded_rng(Some(seed)));
+            assert_eq!(maze.start(), start, "the entrance moved");
+            assert_visits_every_cell(&maze, &format!("start {:?} seed {}", start, seed));
+        }
+    }
+}
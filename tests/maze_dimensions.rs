@@ -0,0 +1,32 @@
+//! Checks that generators turn down mazes they cannot carve instead of panicking
+
+use maze_generator::algorithms::{GenerateError, Registry};
+
+#[test]
+fn empty_mazes_are_invalid_dimensions() {
+    let registry = Registry::builtin();
+    for generator in registry.iter() {
+        for (width, height) in [(0, 10), (10, 0), (0, 0)] {
+            let result = generator.generate(width, height, 0.5, Some(1));
+            assert!(
+                matches!(
+                    result,
+                    Err(GenerateError::InvalidDimensions { width: w, height: h })
+                        if (w, h) == (width, height)
+                ),
+                "{} at {}x{}",
+                generator.name(),
+                width,
+                height
+            );
+            let result = generator.generate_with_events(width, height, 0.5, Some(1));
+            assert!(
+                matches!(result, Err(GenerateError::InvalidDimensions { .. })),
+                "{} with events at {}x{}",
+                generator.name(),
+                width,
+                height
+            );
+        }
+    }
+}
@@ -0,0 +1,39 @@
+//! Regression checks for the random-walk generators at low complexity
+
+use maze_generator::algorithms::{seeded_rng, AldousBroder, MazeGenerator, Wilsons};
+use maze_generator::grid::{Grid, Tiling};
+use maze_generator::hex::HexMaze;
+
+#[test]
+fn drifting_walks_finish_hex_mazes() {
+    // At complexity 0 the walk favours its first neighbour and once never
+    // reached the far side of a hex grid
+    let generators: [&dyn MazeGenerator; 2] = [&AldousBroder::default(), &Wilsons::default()];
+    for generator in generators {
+        for seed in 0..5 {
+            let mut maze = HexMaze::new(15, 12);
+            generator
+                .carve_grid(&mut maze, 0.0, &mut seeded_rng(Some(seed)))
+                .unwrap();
+
+            let passages: usize = maze
+                .cells()
+                .iter()
+                .map(|&cell| maze.links(cell).len())
+                .sum();
+            assert_eq!(
+                passages / 2,
+                maze.active_count() - 1,
+                "{} seed {}",
+                generator.name(),
+                seed
+            );
+            assert!(
+                maze.solve().is_some(),
+                "{} seed {} left the maze unsolvable",
+                generator.name(),
+                seed
+            );
+        }
+    }
+}
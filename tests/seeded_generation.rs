@@ -0,0 +1,38 @@
+//! Checks that a seed reproduces the maze it was printed with
+//!
+//! Every built-in generator carves the same maze twice from one seed, so
+//! none of them may draw from the RNG in an order that changes between runs.
+
+use maze_generator::algorithms::{MazeGenerator, Registry};
+use maze_generator::grid::{CellId, Grid};
+
+/// Open passages of a generated maze, cell by cell
+fn passages(generator: &dyn MazeGenerator, complexity: f64, seed: u64) -> Vec<Vec<CellId>> {
+    let maze = generator
+        .generate(21, 15, complexity, Some(seed))
+        .unwrap_or_else(|err| panic!("{} failed: {}", generator.name(), err))
+        .maze;
+    maze.cells()
+        .into_iter()
+        .map(|cell| maze.links(cell))
+        .collect()
+}
+
+#[test]
+fn same_seed_gives_same_maze() {
+    let registry = Registry::builtin();
+    for generator in registry.iter() {
+        for complexity in [0.0, 0.5, 1.0] {
+            for seed in 0..5 {
+                assert_eq!(
+                    passages(generator, complexity, seed),
+                    passages(generator, complexity, seed),
+                    "{} at complexity {} with seed {}",
+                    generator.name(),
+                    complexity,
+                    seed
+                );
+            }
+        }
+    }
+}
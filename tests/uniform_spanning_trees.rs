@@ -0,0 +1,158 @@
+//! Statistical checks that the uniform spanning tree generators are unbiased
+//!
+//! Every spanning tree of a tiny grid is enumerated, many mazes are generated
+//! over a fixed range of seeds, and a chi-square test compares how often each
+//! tree came up against the flat distribution a uniform sampler must give.
+
+use maze_generator::algorithms::{MazeGenerator, Params, Registry};
+use maze_generator::grid::{CellId, Grid};
+use maze_generator::maze::Maze;
+use std::collections::HashMap;
+
+/// Mazes generated per spanning tree, so each tree is expected this often
+const SAMPLES_PER_TREE: usize = 40;
+
+/// Standard normal quantile for a one-sided p of 0.001
+const Z_999: f64 = 3.090;
+
+/// Walls between neighbouring cells of a plain grid, each once
+fn grid_edges(width: u32, height: u32) -> Vec<(CellId, CellId)> {
+    let maze = Maze::new(width, height);
+    let mut edges = Vec::new();
+    for cell in maze.cells() {
+        for neighbor in maze.neighbors(cell) {
+            if neighbor > cell {
+                edges.push((cell, neighbor));
+            }
+        }
+    }
+    edges
+}
+
+/// Every spanning tree of the grid, as bit sets over `edges`
+fn spanning_trees(cells: usize, edges: &[(CellId, CellId)]) -> Vec<u32> {
+    fn find(parent: &mut [usize], mut x: usize) -> usize {
+        while parent[x] != x {
+            x = parent[x];
+        }
+        x
+    }
+
+    (0u32..1 << edges.len())
+        .filter(|set| set.count_ones() as usize == cells - 1)
+        .filter(|set| {
+            // n - 1 edges without a cycle connect all n cells
+            let mut parent: Vec<usize> = (0..cells).collect();
+            edges.iter().enumerate().all(|(i, &(a, b))| {
+                if set & (1 << i) == 0 {
+                    return true;
+                }
+                let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+                parent[ra] = rb;
+                ra != rb
+            })
+        })
+        .collect()
+}
+
+/// The open walls of a maze as a bit set over `edges`
+fn tree_of(maze: &Maze, edges: &[(CellId, CellId)]) -> u32 {
+    edges
+        .iter()
+        .enumerate()
+        .filter(|&(_, &(a, b))| maze.is_linked(a, b))
+        .fold(0, |set, (i, _)| set | 1 << i)
+}
+
+/// Upper 0.1% point of the chi-square distribution (Wilson-Hilferty)
+fn chi_square_critical(df: f64) -> f64 {
+    let k = 2.0 / (9.0 * df);
+    df * (1.0 - k + Z_999 * k.sqrt()).powi(3)
+}
+
+/// Chi-square statistic of the trees a generator produced, against a flat distribution
+fn chi_square(generator: &dyn MazeGenerator, complexity: f64, width: u32, height: u32) -> (f64, f64) {
+    let edges = grid_edges(width, height);
+    let trees = spanning_trees((width * height) as usize, &edges);
+    let samples = trees.len() * SAMPLES_PER_TREE;
+
+    let mut counts: HashMap<u32, usize> = trees.iter().map(|&tree| (tree, 0)).collect();
+    for seed in 0..samples as u64 {
+        let maze = generator
+            .generate(width, height, complexity, Some(seed))
+            .expect("tiny grids are valid")
+            .maze;
+        let tree = tree_of(&maze, &edges);
+        *counts
+            .get_mut(&tree)
+            .unwrap_or_else(|| panic!("{} did not carve a spanning tree", generator.name())) += 1;
+    }
+
+    let expected = SAMPLES_PER_TREE as f64;
+    let statistic = counts
+        .values()
+        .map(|&observed| (observed as f64 - expected).powi(2) / expected)
+        .sum();
+    (statistic, chi_square_critical((trees.len() - 1) as f64))
+}
+
+fn uniform(name: &str) -> Box<dyn MazeGenerator> {
+    let mut params = Params::new();
+    params.set("uniform", "true");
+    Registry::builtin()
+        .get(name)
+        .expect("built-in generator")
+        .with_params(&params)
+        .expect("uniform is a known parameter")
+}
+
+#[test]
+fn enumerates_known_tree_counts() {
+    // Known spanning tree counts of the 2x2, 2x3 and 3x3 grid graphs
+    assert_eq!(spanning_trees(4, &grid_edges(2, 2)).len(), 4);
+    assert_eq!(spanning_trees(6, &grid_edges(3, 2)).len(), 15);
+    assert_eq!(spanning_trees(9, &grid_edges(3, 3)).len(), 192);
+}
+
+#[test]
+fn aldous_broder_uniform_is_unbiased() {
+    for &(width, height) in &[(3, 2), (3, 3)] {
+        // Complexity is ignored in uniform mode, including the biased low end
+        for &complexity in &[0.0, 0.9] {
+            let (statistic, critical) = chi_square(uniform("aldous_broder").as_ref(), complexity, width, height);
+            assert!(
+                statistic < critical,
+                "aldous_broder {}x{} at {}: chi-square {:.1} above {:.1}",
+                width, height, complexity, statistic, critical
+            );
+        }
+    }
+}
+
+#[test]
+fn wilsons_uniform_is_unbiased() {
+    for &(width, height) in &[(3, 2), (3, 3)] {
+        for &complexity in &[0.0, 0.9] {
+            let (statistic, critical) = chi_square(uniform("wilsons").as_ref(), complexity, width, height);
+            assert!(
+                statistic < critical,
+                "wilsons {}x{} at {}: chi-square {:.1} above {:.1}",
+                width, height, complexity, statistic, critical
+            );
+        }
+    }
+}
+
+#[test]
+fn biased_walk_is_detected() {
+    // Without uniform mode, low complexity prefers the first neighbour; the
+    // test must be strong enough to catch that
+    let registry = Registry::builtin();
+    let generator = registry.get("wilsons").expect("built-in generator");
+    let (statistic, critical) = chi_square(generator, 0.05, 3, 3);
+    assert!(
+        statistic > critical,
+        "chi-square {:.1} did not detect the biased walk (critical {:.1})",
+        statistic, critical
+    );
+}
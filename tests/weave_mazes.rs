@@ -0,0 +1,25 @@
+//! Regression checks for generators carving weave mazes
+
+use maze_generator::algorithms::{seeded_rng, MazeGenerator, Wilsons};
+use maze_generator::grid::Tiling;
+use maze_generator::weave::WeaveMaze;
+
+/// Carve a weave maze the way the CLI does for a given seed
+fn carve(generator: &dyn MazeGenerator, width: u32, height: u32, seed: u64) -> WeaveMaze {
+    let mut maze = WeaveMaze::new(width, height);
+    let mut rng = seeded_rng(Some(seed));
+    generator.carve_grid(&mut maze, 0.5, &mut rng).unwrap();
+    maze
+}
+
+#[test]
+fn wilsons_weave_is_solvable() {
+    // Seed 5 once left a region cut off where a walk crossed a tunnel
+    let maze = carve(&Wilsons::default(), 10, 10, 5);
+    assert!(maze.solve().is_some(), "seed 5 left the weave unsolvable");
+
+    for seed in 0..30 {
+        let maze = carve(&Wilsons::default(), 25, 25, seed);
+        assert!(maze.solve().is_some(), "seed {} left the weave unsolvable", seed);
+    }
+}